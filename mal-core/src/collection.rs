@@ -0,0 +1,509 @@
+//! Higher-order collection library, functions given as arguments are called like `apply` calls
+//! them.
+
+use std::{cmp::Ordering, convert::TryFrom, rc::Rc, slice};
+
+use im_rc::{HashMap, HashSet, Vector};
+use mal_derive::builtin_func;
+
+use crate::{
+    core::conj,
+    env::Env,
+    seq::{self, call, function_arg, int_arg},
+    types::{
+        func::MalFuncPtr, MalBool, MalChar, MalHashMap, MalInt, MalKeyword, MalLazySeq, MalList,
+        MalNil, MalString, MalSymbol, MalType, MalVec, Number,
+    },
+    MalError, MalResult,
+};
+
+fn list(items: Vec<Rc<dyn MalType>>) -> Rc<dyn MalType> {
+    Rc::from(MalList::from(items))
+}
+
+fn filter_items(
+    pred: &Rc<dyn MalType>,
+    coll: &Rc<dyn MalType>,
+    env: &Rc<Env>,
+    builtin: &'static str,
+    keep: bool,
+) -> MalResult {
+    function_arg(pred, builtin, 1)?;
+    if coll.is::<MalLazySeq>() {
+        let filtered = seq::filter_seq(pred.clone(), coll.clone(), env.clone(), keep);
+        return Ok(Rc::from(filtered));
+    }
+    let mut result = Vec::new();
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg(builtin, 2, coll))?;
+        if call(pred, slice::from_ref(&item), env)?.truthy() == keep {
+            result.push(item);
+        }
+    }
+    Ok(list(result))
+}
+
+/// Lazy sequences are filtered lazily, other collections right away.
+#[builtin_func]
+pub fn filter(pred: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    filter_items(pred, coll, env, "filter", true)
+}
+
+#[builtin_func]
+pub fn remove(pred: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    filter_items(pred, coll, env, "remove", false)
+}
+
+/// `(reduce f coll)` starts with the first item, or calls `(f)` when `coll` is empty,
+/// `(reduce f init coll)` starts with `init`.
+#[builtin_func]
+pub fn reduce(
+    func: &Rc<dyn MalType>,
+    first: &Rc<dyn MalType>,
+    second: Option<&Rc<dyn MalType>>,
+    env: &Rc<Env>,
+) -> MalResult {
+    function_arg(func, "reduce", 1)?;
+    let (init, coll, position) = match second {
+        Some(coll) => (Some(first.clone()), coll, 3),
+        None => (None, first, 2),
+    };
+    let mut items =
+        seq::iter(coll).map(|item| item.map_err(|err| err.for_arg("reduce", position, coll)));
+    let mut acc = match init {
+        Some(init) => init,
+        None => match items.next() {
+            Some(item) => item?,
+            None => return call(func, &[], env),
+        },
+    };
+    for item in items {
+        acc = call(func, &[acc, item?], env)?;
+    }
+    Ok(acc)
+}
+
+/// Natural order of numbers, strings, keywords, symbols and characters.
+fn compare(lhs: &Rc<dyn MalType>, rhs: &Rc<dyn MalType>) -> Result<Ordering, MalError> {
+    let ordering = if let (Ok(lhs), Ok(rhs)) = (
+        Number::try_from(lhs.as_ref()),
+        Number::try_from(rhs.as_ref()),
+    ) {
+        lhs.partial_cmp(&rhs)
+    } else if let (Ok(lhs), Ok(rhs)) = (lhs.as_type::<MalString>(), rhs.as_type::<MalString>()) {
+        Some(lhs.as_str().cmp(rhs.as_str()))
+    } else if let (Ok(lhs), Ok(rhs)) = (lhs.as_type::<MalKeyword>(), rhs.as_type::<MalKeyword>()) {
+        Some(lhs.value.cmp(&rhs.value))
+    } else if let (Ok(lhs), Ok(rhs)) = (lhs.as_type::<MalSymbol>(), rhs.as_type::<MalSymbol>()) {
+        Some(lhs.as_str().cmp(rhs.as_str()))
+    } else if let (Ok(lhs), Ok(rhs)) = (lhs.as_type::<MalChar>(), rhs.as_type::<MalChar>()) {
+        Some(lhs.value().cmp(&rhs.value()))
+    } else {
+        None
+    };
+    match ordering {
+        Some(ordering) => Ok(ordering),
+        None => Err(MalError::NotComparable(lhs.clone(), rhs.clone())),
+    }
+}
+
+/// Order given by a comparator, which returns a number that's negative when `lhs` comes first
+/// or a boolean that's true when `lhs` comes first, like `<`.
+fn compare_with(
+    comparator: &Rc<dyn MalType>,
+    lhs: &Rc<dyn MalType>,
+    rhs: &Rc<dyn MalType>,
+    env: &Rc<Env>,
+) -> Result<Ordering, MalError> {
+    let result = call(comparator, &[lhs.clone(), rhs.clone()], env)?;
+    if let Ok(number) = Number::try_from(result.as_ref()) {
+        return Ok(number.as_f64().partial_cmp(&0.0).unwrap_or(Ordering::Equal));
+    }
+    if result.truthy() {
+        Ok(Ordering::Less)
+    } else if call(comparator, &[rhs.clone(), lhs.clone()], env)?.truthy() {
+        Ok(Ordering::Greater)
+    } else {
+        Ok(Ordering::Equal)
+    }
+}
+
+/// Stable merge sort, unlike `slice::sort_by` the comparison can fail.
+fn merge_sort<T, F>(mut items: Vec<T>, compare: &mut F) -> Result<Vec<T>, MalError>
+where
+    F: FnMut(&T, &T) -> Result<Ordering, MalError>,
+{
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let mut left = merge_sort(items, compare)?.into_iter().peekable();
+    let mut right = merge_sort(right, compare)?.into_iter().peekable();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    while let (Some(lhs), Some(rhs)) = (left.peek(), right.peek()) {
+        // Equal items are taken from the left, which keeps the sort stable
+        let next = if compare(lhs, rhs)? == Ordering::Greater {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+/// `(sort coll)` sorts in natural order, `(sort comparator coll)` with the comparator.
+#[builtin_func]
+pub fn sort(first: &Rc<dyn MalType>, second: Option<&Rc<dyn MalType>>, env: &Rc<Env>) -> MalResult {
+    let (comparator, coll) = match second {
+        Some(coll) => {
+            function_arg(first, "sort", 1)?;
+            (Some(first), coll)
+        }
+        None => (None, first),
+    };
+    let items: Vec<_> = seq::iter(coll)
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.for_arg("sort", if comparator.is_some() { 2 } else { 1 }, coll))?;
+    let sorted = match comparator {
+        Some(comparator) => merge_sort(items, &mut |lhs, rhs| {
+            compare_with(comparator, lhs, rhs, env)
+        })?,
+        None => merge_sort(items, &mut compare)?,
+    };
+    Ok(list(sorted))
+}
+
+/// `(sort-by keyfn coll)` sorts by the keys `keyfn` returns for the items, in natural order or
+/// with a comparator given as `(sort-by keyfn comparator coll)`.
+#[builtin_func(symbol = "sort-by")]
+pub fn sort_by(
+    keyfn: &Rc<dyn MalType>,
+    second: &Rc<dyn MalType>,
+    third: Option<&Rc<dyn MalType>>,
+    env: &Rc<Env>,
+) -> MalResult {
+    function_arg(keyfn, "sort-by", 1)?;
+    let (comparator, coll, position) = match third {
+        Some(coll) => {
+            function_arg(second, "sort-by", 2)?;
+            (Some(second), coll, 3)
+        }
+        None => (None, second, 2),
+    };
+    let mut keyed = Vec::new();
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("sort-by", position, coll))?;
+        keyed.push((call(keyfn, slice::from_ref(&item), env)?, item));
+    }
+    let sorted = match comparator {
+        Some(comparator) => merge_sort(keyed, &mut |(lhs, _), (rhs, _)| {
+            compare_with(comparator, lhs, rhs, env)
+        })?,
+        None => merge_sort(keyed, &mut |(lhs, _), (rhs, _)| compare(lhs, rhs))?,
+    };
+    Ok(list(sorted.into_iter().map(|(_, item)| item).collect()))
+}
+
+/// Map from the values of `func` to vectors of the items giving them, in order.
+#[builtin_func(symbol = "group-by")]
+pub fn group_by(func: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    function_arg(func, "group-by", 1)?;
+    let mut groups: HashMap<Rc<dyn MalType>, Vector<Rc<dyn MalType>>> = HashMap::new();
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("group-by", 2, coll))?;
+        let key = call(func, slice::from_ref(&item), env)?;
+        groups.entry(key).or_default().push_back(item);
+    }
+    let map: HashMap<_, _> = groups
+        .into_iter()
+        .map(|(key, items)| (key, Rc::from(MalVec::from(items)) as Rc<dyn MalType>))
+        .collect();
+    Ok(Rc::from(MalHashMap::from(map)))
+}
+
+/// Map from the distinct items to the number of times they occur.
+#[builtin_func]
+pub fn frequencies(coll: &Rc<dyn MalType>) -> MalResult {
+    let mut counts: HashMap<Rc<dyn MalType>, i64> = HashMap::new();
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("frequencies", 1, coll))?;
+        *counts.entry(item).or_default() += 1;
+    }
+    let map: HashMap<_, _> = counts
+        .into_iter()
+        .map(|(item, count)| (item, Rc::from(MalInt::from(count)) as Rc<dyn MalType>))
+        .collect();
+    Ok(Rc::from(MalHashMap::from(map)))
+}
+
+/// `(partition n coll)` splits the items in lists of `n` items, `(partition n step coll)`
+/// starts a list every `step` items. Items left over at the end are dropped.
+#[builtin_func]
+pub fn partition(
+    size: &Rc<dyn MalType>,
+    second: &Rc<dyn MalType>,
+    third: Option<&Rc<dyn MalType>>,
+) -> MalResult {
+    let positive = |value: &Rc<dyn MalType>, position| match int_arg(value, "partition", position)?
+    {
+        count if count > 0 => Ok(count as usize),
+        _ => Err(MalError::WrongType("positive integer").for_arg("partition", position, value)),
+    };
+    let size = positive(size, 1)?;
+    let (step, coll, position) = match third {
+        Some(coll) => (positive(second, 2)?, coll, 3),
+        None => (size, second, 2),
+    };
+    let items = seq::to_vector(coll).map_err(|err| err.for_arg("partition", position, coll))?;
+    let mut result = Vec::new();
+    let mut start = 0;
+    while start + size <= items.len() {
+        let part = MalList::from(items.skip(start).take(size));
+        result.push(Rc::from(part) as Rc<dyn MalType>);
+        start += step;
+    }
+    Ok(list(result))
+}
+
+/// First item of each collection, then the second of each and so on, until one of them runs
+/// out of items.
+#[builtin_func]
+pub fn interleave(colls: &[Rc<dyn MalType>]) -> MalResult {
+    let mut iters: Vec<_> = colls.iter().map(seq::iter).collect();
+    let mut result = Vec::new();
+    if iters.is_empty() {
+        return Ok(list(result));
+    }
+    loop {
+        let mut round = Vec::with_capacity(iters.len());
+        for (index, iter) in iters.iter_mut().enumerate() {
+            match iter.next() {
+                Some(item) => round
+                    .push(item.map_err(|err| err.for_arg("interleave", index + 1, &colls[index]))?),
+                None => return Ok(list(result)),
+            }
+        }
+        result.extend(round);
+    }
+}
+
+/// Items without the ones equal to an earlier item.
+#[builtin_func]
+pub fn distinct(coll: &Rc<dyn MalType>) -> MalResult {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("distinct", 1, coll))?;
+        if seen.insert(item.clone()).is_none() {
+            result.push(item);
+        }
+    }
+    Ok(list(result))
+}
+
+/// First truthy value `pred` returns for an item, or nil.
+#[builtin_func]
+pub fn some(pred: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    function_arg(pred, "some", 1)?;
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("some", 2, coll))?;
+        let value = call(pred, slice::from_ref(&item), env)?;
+        if value.truthy() {
+            return Ok(value);
+        }
+    }
+    Ok(MalNil::new())
+}
+
+#[builtin_func(symbol = "every?")]
+pub fn every(pred: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    function_arg(pred, "every?", 1)?;
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("every?", 2, coll))?;
+        if !call(pred, slice::from_ref(&item), env)?.truthy() {
+            return Ok(Rc::from(MalBool::from(false)));
+        }
+    }
+    Ok(Rc::from(MalBool::from(true)))
+}
+
+/// Map from the keys to the values at the same position, extra keys or values are ignored.
+#[builtin_func]
+pub fn zipmap(keys: &Rc<dyn MalType>, values: &Rc<dyn MalType>) -> MalResult {
+    let mut map = HashMap::new();
+    for (key, value) in seq::iter(keys).zip(seq::iter(values)) {
+        let key = key.map_err(|err| err.for_arg("zipmap", 1, keys))?;
+        let value = value.map_err(|err| err.for_arg("zipmap", 2, values))?;
+        map.insert(key, value);
+    }
+    Ok(Rc::from(MalHashMap::from(map)))
+}
+
+/// Add the items of `from` to `to` as `conj` does, items added to a hash-map are `[key value]`
+/// pairs.
+#[builtin_func]
+pub fn into(to: &Rc<dyn MalType>, from: &Rc<dyn MalType>) -> MalResult {
+    let items = seq::to_vector(from).map_err(|err| err.for_arg("into", 2, from))?;
+    if let Ok(map) = to.as_type::<MalHashMap>() {
+        let mut entries = Vec::with_capacity(items.len() * 2);
+        for item in &items {
+            match item.as_array() {
+                Ok(pair) if pair.len() == 2 => entries.extend(pair.iter().cloned()),
+                _ => return Err(MalError::WrongType("[key value] pair").for_arg("into", 2, item)),
+            }
+        }
+        return Ok(Rc::from(map.insert(entries.into_iter())?));
+    }
+    let to = if to.is::<MalNil>() {
+        Rc::from(MalList::new())
+    } else {
+        to.clone()
+    };
+    if items.is_empty() {
+        return Ok(to);
+    }
+    let items: Vec<_> = items.into_iter().collect();
+    conj(&to, &items)
+}
+
+/// Value at `key` of a hash-map or at index `key` of a vector, `None` for other values.
+fn lookup(coll: &Rc<dyn MalType>, key: &Rc<dyn MalType>) -> Option<Rc<dyn MalType>> {
+    if let Ok(map) = coll.as_type::<MalHashMap>() {
+        map.get(key.as_ref()).cloned()
+    } else if let (Ok(vector), Ok(index)) = (coll.as_type::<MalVec>(), key.as_type::<MalInt>()) {
+        let index = usize::try_from(index.value()).ok()?;
+        vector.get(index).cloned()
+    } else {
+        None
+    }
+}
+
+/// Copy of a hash-map or vector with `value` at `key`, nil is seen as an empty hash-map.
+fn associate(
+    coll: &Rc<dyn MalType>,
+    key: &Rc<dyn MalType>,
+    value: Rc<dyn MalType>,
+    builtin: &'static str,
+) -> MalResult {
+    if coll.is::<MalNil>() {
+        let map = MalHashMap::new().insert(vec![key.clone(), value].into_iter())?;
+        Ok(Rc::from(map))
+    } else if let Ok(map) = coll.as_type::<MalHashMap>() {
+        Ok(Rc::from(map.insert(vec![key.clone(), value].into_iter())?))
+    } else if let Ok(vector) = coll.as_type::<MalVec>() {
+        let index = key
+            .as_type::<MalInt>()
+            .map_err(|err| err.for_arg(builtin, 2, key))?;
+        let mut items = vector.values().clone();
+        match usize::try_from(index.value()) {
+            Ok(index) if index < items.len() => {
+                items.set(index, value);
+            }
+            Ok(index) if index == items.len() => items.push_back(value),
+            Ok(index) => {
+                return Err(MalError::OutOfBounds {
+                    idx: index,
+                    len: items.len(),
+                })
+            }
+            Err(_) => return Err(MalError::NegativeIndex(index.value())),
+        }
+        Ok(Rc::from(MalVec::from(items)))
+    } else {
+        let err = MalError::WrongType("hash-map, vector or nil");
+        Err(err.for_arg(builtin, 1, coll))
+    }
+}
+
+/// Copy of `coll` with the value nested at `keys` replaced by what `update` returns for it.
+fn update_path(
+    coll: &Rc<dyn MalType>,
+    keys: &[Rc<dyn MalType>],
+    update: &mut dyn FnMut(Rc<dyn MalType>) -> MalResult,
+    builtin: &'static str,
+) -> MalResult {
+    let (key, rest) = match keys.split_first() {
+        Some(split) => split,
+        None => return update(coll.clone()),
+    };
+    let current = lookup(coll, key).unwrap_or_else(|| MalNil::new());
+    let value = update_path(&current, rest, update, builtin)?;
+    associate(coll, key, value, builtin)
+}
+
+fn apply_update(
+    func: &Rc<dyn MalType>,
+    value: Rc<dyn MalType>,
+    args: &[Rc<dyn MalType>],
+    env: &Rc<Env>,
+) -> MalResult {
+    let mut call_args = Vec::with_capacity(args.len() + 1);
+    call_args.push(value);
+    call_args.extend(args.iter().cloned());
+    call(func, &call_args, env)
+}
+
+/// `(update m k f args...)` replaces the value at `k` with `(f value args...)`.
+#[builtin_func]
+pub fn update(
+    coll: &Rc<dyn MalType>,
+    key: &Rc<dyn MalType>,
+    func: &Rc<dyn MalType>,
+    args: &[Rc<dyn MalType>],
+    env: &Rc<Env>,
+) -> MalResult {
+    function_arg(func, "update", 3)?;
+    let mut update = |value| apply_update(func, value, args, env);
+    update_path(coll, slice::from_ref(key), &mut update, "update")
+}
+
+/// Value nested in hash-maps and vectors at `keys`, or `not-found` when it's missing.
+#[builtin_func(symbol = "get-in")]
+pub fn get_in(
+    coll: &Rc<dyn MalType>,
+    keys: &Rc<dyn MalType>,
+    not_found: Option<&Rc<dyn MalType>>,
+) -> MalResult {
+    let mut value = coll.clone();
+    for key in seq::iter(keys) {
+        let key = key.map_err(|err| err.for_arg("get-in", 2, keys))?;
+        value = match lookup(&value, &key) {
+            Some(value) => value,
+            None => return Ok(not_found.cloned().unwrap_or_else(|| MalNil::new())),
+        };
+    }
+    Ok(value)
+}
+
+/// Copy of `coll` with `value` nested at `keys`, missing levels are created as hash-maps.
+#[builtin_func(symbol = "assoc-in")]
+pub fn assoc_in(
+    coll: &Rc<dyn MalType>,
+    keys: &Rc<dyn MalType>,
+    value: &Rc<dyn MalType>,
+) -> MalResult {
+    let keys: Vec<_> = seq::iter(keys)
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.for_arg("assoc-in", 2, keys))?;
+    update_path(coll, &keys, &mut |_| Ok(value.clone()), "assoc-in")
+}
+
+/// `(update-in m ks f args...)` replaces the value nested at `ks` with `(f value args...)`.
+#[builtin_func(symbol = "update-in")]
+pub fn update_in(
+    coll: &Rc<dyn MalType>,
+    keys: &Rc<dyn MalType>,
+    func: &Rc<dyn MalType>,
+    args: &[Rc<dyn MalType>],
+    env: &Rc<Env>,
+) -> MalResult {
+    function_arg(func, "update-in", 3)?;
+    let keys: Vec<_> = seq::iter(keys)
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.for_arg("update-in", 2, keys))?;
+    let mut update = |value| apply_update(func, value, args, env);
+    update_path(coll, &keys, &mut update, "update-in")
+}
@@ -1,39 +1,54 @@
-use std::{convert::TryInto, fmt::Write, fs, io, iter, rc::Rc, time::SystemTime};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::Write,
+    fs, io,
+    rc::Rc,
+    slice,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
 
+use im_rc::Vector;
 use mal_derive::builtin_func;
 
 use crate::{
     env::{self, Env},
-    eval, read,
+    eval,
+    namespace::load_source,
+    read_source,
+    reader::Source,
+    seq,
     types::{
-        func::MalFuncPtr, MalAtom, MalBool, MalClojure, MalFunc, MalHashMap, MalInt, MalKeyword,
-        MalList, MalNil, MalString, MalSymbol, MalType, MalVec,
+        func::MalFuncPtr, MalAtom, MalBigInt, MalBool, MalChar, MalClojure, MalFloat, MalFunc,
+        MalHashMap, MalInt, MalKeyword, MalLazySeq, MalList, MalNil, MalSet, MalString, MalSymbol,
+        MalType, MalVec, Number,
     },
     MalError, MalResult,
 };
 
-#[builtin_func(symbol = "+")]
-pub fn add(lhs: &MalInt, rhs: &MalInt) -> MalResult {
-    Ok(Rc::from(lhs + rhs))
+#[builtin_func(symbol = "+", fold, identity = 0)]
+pub fn add(lhs: Number, rhs: Number) -> MalResult {
+    Ok((lhs + rhs).into())
 }
 
-#[builtin_func(symbol = "-")]
-pub fn subtract(lhs: &MalInt, rhs: &MalInt) -> MalResult {
-    Ok(Rc::from(lhs - rhs))
+#[builtin_func(symbol = "-", fold, unary = 0)]
+pub fn subtract(lhs: Number, rhs: Number) -> MalResult {
+    Ok((lhs - rhs).into())
 }
 
-#[builtin_func(symbol = "*")]
-pub fn multiply(lhs: &MalInt, rhs: &MalInt) -> MalResult {
-    Ok(Rc::from(lhs * rhs))
+#[builtin_func(symbol = "*", fold, identity = 1)]
+pub fn multiply(lhs: Number, rhs: Number) -> MalResult {
+    Ok((lhs * rhs).into())
 }
 
-#[builtin_func(symbol = "/")]
-pub fn divide(lhs: &MalInt, rhs: &MalInt) -> MalResult {
-    Ok(Rc::from(lhs / rhs))
+#[builtin_func(symbol = "/", fold, unary = 1)]
+pub fn divide(lhs: Number, rhs: Number) -> MalResult {
+    Ok((lhs / rhs)?.into())
 }
 
 #[builtin_func]
 pub fn prn(args: &[Rc<dyn MalType>]) -> MalResult {
+    args.iter().try_for_each(seq::realize)?;
     if !args.is_empty() {
         print!("{:?}", args[0]);
         for arg in &args[1..] {
@@ -46,6 +61,7 @@ pub fn prn(args: &[Rc<dyn MalType>]) -> MalResult {
 
 #[builtin_func(name = "println")]
 pub fn println_fn(args: &[Rc<dyn MalType>]) -> MalResult {
+    args.iter().try_for_each(seq::realize)?;
     if !args.is_empty() {
         print!("{}", args[0]);
         for arg in &args[1..] {
@@ -69,49 +85,64 @@ pub fn is_list(obj: &dyn MalType) -> MalResult {
 
 #[builtin_func(symbol = "empty?")]
 pub fn is_empty(obj: &dyn MalType) -> MalResult {
-    let value = match obj.as_array() {
-        Ok(arr) => arr.is_empty(),
-        Err(_) => true,
+    let value = match obj.uncons() {
+        Ok(cons) => cons.is_none(),
+        // Values that aren't sequences have no items
+        Err(_) if !obj.is::<MalLazySeq>() => true,
+        Err(err) => return Err(err),
     };
     Ok(Rc::from(MalBool::from(value)))
 }
 
 #[builtin_func]
-pub fn count(obj: &dyn MalType) -> MalResult {
-    let value = match obj.as_array() {
-        Ok(arr) => arr.len() as i64,
-        Err(_) => 0,
+pub fn count(obj: &Rc<dyn MalType>) -> MalResult {
+    let value = if let Ok(arr) = obj.as_array() {
+        arr.len()
+    } else if let Ok(set) = obj.as_type::<MalSet>() {
+        set.len()
+    } else if let Ok(map) = obj.as_type::<MalHashMap>() {
+        map.len()
+    } else {
+        let mut count = 0;
+        for item in seq::iter(obj) {
+            item.map_err(|err| err.for_arg("count", 1, obj))?;
+            count += 1;
+        }
+        count
     };
-    Ok(Rc::from(MalInt::from(value)))
+    Ok(Rc::from(MalInt::from(value as i64)))
 }
 
 #[builtin_func(symbol = "=")]
-pub fn equal(lhs: &dyn MalType, rhs: &dyn MalType) -> MalResult {
-    Ok(Rc::from(MalBool::from(lhs.equal(rhs))))
+pub fn equal(lhs: &Rc<dyn MalType>, rhs: &Rc<dyn MalType>) -> MalResult {
+    seq::realize(lhs)?;
+    seq::realize(rhs)?;
+    Ok(Rc::from(MalBool::from(lhs.equal(rhs.as_ref()))))
 }
 
-#[builtin_func(symbol = "<")]
-pub fn lt(lhs: &MalInt, rhs: &MalInt) -> MalResult {
+#[builtin_func(symbol = "<", chain)]
+pub fn lt(lhs: Number, rhs: Number) -> MalResult {
     Ok(Rc::from(MalBool::from(lhs < rhs)))
 }
 
-#[builtin_func(symbol = "<=")]
-pub fn leq(lhs: &MalInt, rhs: &MalInt) -> MalResult {
+#[builtin_func(symbol = "<=", chain)]
+pub fn leq(lhs: Number, rhs: Number) -> MalResult {
     Ok(Rc::from(MalBool::from(lhs <= rhs)))
 }
 
-#[builtin_func(symbol = ">")]
-pub fn gt(lhs: &MalInt, rhs: &MalInt) -> MalResult {
+#[builtin_func(symbol = ">", chain)]
+pub fn gt(lhs: Number, rhs: Number) -> MalResult {
     Ok(Rc::from(MalBool::from(lhs > rhs)))
 }
 
-#[builtin_func(symbol = ">=")]
-pub fn geq(lhs: &MalInt, rhs: &MalInt) -> MalResult {
+#[builtin_func(symbol = ">=", chain)]
+pub fn geq(lhs: Number, rhs: Number) -> MalResult {
     Ok(Rc::from(MalBool::from(lhs >= rhs)))
 }
 
 #[builtin_func(symbol = "pr-str")]
 pub fn pr_str(args: &[Rc<dyn MalType>]) -> MalResult {
+    args.iter().try_for_each(seq::realize)?;
     let mut string = String::new();
     if !args.is_empty() {
         string.write_fmt(format_args!("{:?}", &args[0])).unwrap();
@@ -124,6 +155,7 @@ pub fn pr_str(args: &[Rc<dyn MalType>]) -> MalResult {
 
 #[builtin_func(name = "str")]
 pub fn str_fn(args: &[Rc<dyn MalType>]) -> MalResult {
+    args.iter().try_for_each(seq::realize)?;
     let mut string = String::new();
     for arg in args {
         string.write_str(&arg.to_string()).unwrap();
@@ -133,9 +165,10 @@ pub fn str_fn(args: &[Rc<dyn MalType>]) -> MalResult {
 
 #[builtin_func(symbol = "read-string")]
 pub fn read_string(string: &MalString) -> MalResult {
-    match read(string.as_str()) {
+    let source = Rc::from(Source::from(string.as_str()));
+    match read_source(&source) {
         Ok(ast) => Ok(ast),
-        Err(_) => todo!(),
+        Err(err) => Err(MalError::from_parse(err, &source)),
     }
 }
 
@@ -147,6 +180,17 @@ pub fn slurp(path: &MalString) -> MalResult {
     }
 }
 
+#[builtin_func(symbol = "load-file")]
+pub fn load_file(path: &MalString, env: &Rc<Env>) -> MalResult {
+    let text = match fs::read_to_string(path.as_str()) {
+        Ok(text) => text,
+        Err(_) => return Err(MalError::IOError),
+    };
+    let source = Rc::from(Source::new(path.as_str(), text));
+    load_source(&source, env::namespace_of(env))?;
+    Ok(MalNil::new())
+}
+
 #[builtin_func]
 pub fn atom(value: &Rc<dyn MalType>) -> MalResult {
     Ok(Rc::from(MalAtom::from(value.clone())))
@@ -178,92 +222,85 @@ pub fn swap(
     if let Ok(func) = callable.as_type() {
         atom.update_with_fn(func, args, env)
     } else if let Ok(clojure) = callable.as_type() {
-        atom.update_with_clojure(clojure, args, env)
+        atom.update_with_clojure(clojure, args)
     } else {
-        Err(MalError::TypeError)
+        Err(MalError::WrongType("function").for_arg("swap!", 2, callable))
     }
 }
 
 #[builtin_func(name = "eval")]
 pub fn eval_fn(ast: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
-    let env = env::global(env);
-    eval(ast.clone(), env)
+    eval(ast.clone(), env::namespace_of(env))
 }
 
 #[builtin_func]
 pub fn cons(elem: &Rc<dyn MalType>, list: &Rc<dyn MalType>) -> MalResult {
-    let list: MalList = iter::repeat(elem)
-        .take(1)
-        .chain(list.as_array()?.iter())
-        .collect();
-    Ok(Rc::from(list))
+    // Lazy sequences stay lazy so that they can be built recursively
+    if list.is::<MalLazySeq>() {
+        return Ok(Rc::from(MalLazySeq::cons(elem.clone(), list.clone())));
+    }
+    let mut list = seq::to_vector(list).map_err(|err| err.for_arg("cons", 2, list))?;
+    list.push_front(elem.clone());
+    Ok(Rc::from(MalList::from(list)))
 }
 
 #[builtin_func]
 pub fn concat(elems: &[Rc<dyn MalType>]) -> MalResult {
-    if elems.is_empty() {
-        return Ok(Rc::from(MalList::default()));
-    }
-    let is_iterable = elems
-        .iter()
-        .any(|item| !item.is::<MalList>() || !item.is::<MalVec>());
-    if !is_iterable {
-        return Err(MalError::TypeError);
-    }
-    let list: MalList = elems
-        .iter()
-        .map(|item| item.as_array().unwrap().iter())
-        .flatten()
-        .collect();
-    Ok(Rc::from(list))
+    let mut list = Vector::new();
+    for (index, elem) in elems.iter().enumerate() {
+        let items = seq::to_vector(elem).map_err(|err| err.for_arg("concat", index + 1, elem))?;
+        list.append(items);
+    }
+    Ok(Rc::from(MalList::from(list)))
 }
 
 #[builtin_func]
 pub fn vec(list: &Rc<dyn MalType>) -> MalResult {
-    Ok(Rc::from(MalVec::from(Vec::from(list.as_array()?))))
+    let items = seq::to_vector(list).map_err(|err| err.for_arg("vec", 1, list))?;
+    Ok(Rc::from(MalVec::from(items)))
 }
 
 #[builtin_func]
-pub fn nth(arr: &Rc<dyn MalType>, idx: &MalInt) -> MalResult {
-    let arr = arr.as_array()?;
+pub fn nth(coll: &Rc<dyn MalType>, idx: &MalInt) -> MalResult {
     let idx: usize = match (*idx).try_into() {
         Ok(idx) => idx,
-        Err(_) => return Err(MalError::TypeError),
+        Err(_) => return Err(MalError::NegativeIndex(idx.value())),
     };
-    match arr.get(idx) {
-        Some(result) => Ok(result.clone()),
-        None => Err(MalError::OutOfBounds {
-            idx,
-            len: arr.len(),
-        }),
+    if let Ok(arr) = coll.as_array() {
+        return match arr.get(idx) {
+            Some(result) => Ok(result.clone()),
+            None => Err(MalError::OutOfBounds {
+                idx,
+                len: arr.len(),
+            }),
+        };
+    }
+    let mut len = 0;
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("nth", 1, coll))?;
+        if len == idx {
+            return Ok(item);
+        }
+        len += 1;
     }
+    Err(MalError::OutOfBounds { idx, len })
 }
 
 #[builtin_func]
-pub fn first(list_or_vec: &Rc<dyn MalType>) -> MalResult {
-    if let Ok(arr) = list_or_vec.as_array() {
-        match arr.get(0) {
-            Some(result) => Ok(result.clone()),
-            None => Ok(MalNil::new()),
-        }
-    } else if list_or_vec.is::<MalNil>() {
-        Ok(list_or_vec.clone())
-    } else {
-        Err(MalError::TypeError)
+pub fn first(coll: &Rc<dyn MalType>) -> MalResult {
+    match coll.uncons() {
+        Ok(Some((first, _))) => Ok(first),
+        Ok(None) => Ok(MalNil::new()),
+        Err(err) => Err(err.for_arg("first", 1, coll)),
     }
 }
 
 #[builtin_func]
-pub fn rest(list_or_vec: &Rc<dyn MalType>) -> MalResult {
-    if list_or_vec.is::<MalNil>() {
-        return Ok(Rc::from(MalList::new()));
-    }
-    let arr = list_or_vec.as_array()?;
-    if arr.is_empty() {
-        Ok(Rc::from(MalList::new()))
-    } else {
-        let r: MalList = arr.iter().skip(1).cloned().collect();
-        Ok(Rc::from(r))
+pub fn rest(coll: &Rc<dyn MalType>) -> MalResult {
+    match coll.uncons() {
+        Ok(Some((_, rest))) => Ok(rest),
+        Ok(None) => Ok(Rc::from(MalList::new())),
+        Err(err) => Err(err.for_arg("rest", 1, coll)),
     }
 }
 
@@ -275,42 +312,40 @@ pub fn throw(value: &Rc<dyn MalType>) -> MalResult {
 #[builtin_func]
 pub fn apply(func: &Rc<dyn MalType>, args: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
     if args.is_empty() {
-        return Err(MalError::TypeError);
+        return Err(MalError::WrongArity {
+            name: String::from("apply"),
+            expected: String::from("at least 2"),
+            actual: 1,
+        });
     }
     let len = args.len();
     let regular_args = &args[0..len - 1];
-    let list_args = match args.last().unwrap().as_array() {
-        Ok(arr) => arr,
-        Err(_) => return Err(MalError::TypeError),
-    };
-    let args: Vec<_> = regular_args.iter().chain(list_args).cloned().collect();
+    let last = &args[len - 1];
+    let list_args = seq::to_vector(last).map_err(|err| err.for_arg("apply", len + 1, last))?;
+    let args: Vec<_> = regular_args.iter().cloned().chain(list_args).collect();
     if let Ok(func) = func.as_type::<MalFunc>() {
         func.call(&args, env)
     } else if let Ok(clojure) = func.as_type::<MalClojure>() {
-        let (ast, env) = clojure.call(&args, env)?;
-        eval(ast, &env)
+        clojure.apply(&args)
     } else {
-        Err(MalError::TypeError)
+        Err(MalError::WrongType("function").for_arg("apply", 1, func))
     }
 }
 
+/// Lazy sequences are mapped lazily, other collections right away.
 #[builtin_func]
-pub fn map(func: &Rc<dyn MalType>, args: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
-    let arr = args.as_array()?;
-    let len = arr.len();
-    let mut result = Vec::with_capacity(len);
-
-    if let Ok(func) = func.as_type::<MalFunc>() {
-        for i in 0..len {
-            result.push(func.call(&arr[i..i + 1], env)?);
-        }
-    } else if let Ok(clojure) = func.as_type::<MalClojure>() {
-        for i in 0..len {
-            let (ast, env) = clojure.call(&arr[i..i + 1], env)?;
-            result.push(eval(ast, &env)?);
-        }
-    } else {
-        return Err(MalError::TypeError);
+pub fn map(func: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    if !seq::is_callable(func.as_ref()) {
+        return Err(MalError::WrongType("function").for_arg("map", 1, func));
+    }
+    if coll.is::<MalLazySeq>() {
+        let mapped = seq::map_seq(func.clone(), coll.clone(), env.clone());
+        return Ok(Rc::from(mapped));
+    }
+    let mut result = Vec::new();
+    for item in seq::iter(coll) {
+        let item = item.map_err(|err| err.for_arg("map", 2, coll))?;
+        result.push(seq::call(func, slice::from_ref(&item), env)?);
     }
     Ok(Rc::from(MalList::from(result)))
 }
@@ -346,6 +381,24 @@ pub fn symbol(string: &MalString) -> MalResult {
     Ok(Rc::from(MalSymbol::from(string.as_str())))
 }
 
+static GENSYM_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// `(gensym)` returns a new symbol that can't clash with the symbols of a macro's arguments.
+#[builtin_func]
+pub fn gensym(prefix: Option<&Rc<dyn MalType>>) -> MalResult {
+    let prefix = match prefix {
+        Some(prefix) => prefix
+            .as_type::<MalString>()
+            .map_err(|err| err.for_arg("gensym", 1, prefix))?
+            .as_str(),
+        None => "G__",
+    };
+    let id = GENSYM_COUNTER.fetch_add(1, Ordering::Relaxed);
+    Ok(Rc::from(MalSymbol::from(
+        format!("{}{}", prefix, id).as_str(),
+    )))
+}
+
 #[builtin_func(symbol = "keyword?")]
 pub fn is_keyword(obj: &dyn MalType) -> MalResult {
     Ok(Rc::from(MalBool::from(obj.is::<MalKeyword>())))
@@ -358,13 +411,14 @@ pub fn keyword(arg: &Rc<dyn MalType>) -> MalResult {
     } else if let Ok(string) = arg.as_type::<MalString>() {
         Ok(Rc::from(MalKeyword::from(format!(":{}", string))))
     } else {
-        Err(MalError::TypeError)
+        Err(MalError::WrongType("string or keyword").for_arg("keyword", 1, arg))
     }
 }
 
 #[builtin_func]
 pub fn vector(args: &[Rc<dyn MalType>]) -> MalResult {
-    Ok(Rc::from(MalVec::from(Vec::from(args))))
+    let vector: MalVec = args.iter().collect();
+    Ok(Rc::from(vector))
 }
 
 #[builtin_func(symbol = "vector?")]
@@ -374,13 +428,18 @@ pub fn is_vector(obj: &dyn MalType) -> MalResult {
 
 #[builtin_func(symbol = "sequential?")]
 pub fn is_sequential(obj: &dyn MalType) -> MalResult {
-    Ok(Rc::from(MalBool::from(obj.as_array().is_ok())))
+    let value = obj.as_array().is_ok() || obj.is::<MalLazySeq>();
+    Ok(Rc::from(MalBool::from(value)))
 }
 
 #[builtin_func(symbol = "hash-map")]
 pub fn hash_map(args: &[Rc<dyn MalType>]) -> MalResult {
-    if args.len() % 2 != 0 {
-        return Err(MalError::TypeError);
+    if !args.len().is_multiple_of(2) {
+        return Err(MalError::WrongArity {
+            name: String::from("hash-map"),
+            expected: String::from("an even number of"),
+            actual: args.len(),
+        });
     }
     let items = args.iter().cloned();
     let map = MalHashMap::try_from_iter(items)?;
@@ -394,8 +453,13 @@ pub fn is_map(obj: &dyn MalType) -> MalResult {
 
 #[builtin_func]
 pub fn assoc(map: &MalHashMap, args: &[Rc<dyn MalType>]) -> MalResult {
-    if args.len() % 2 != 0 {
-        return Err(MalError::TypeError);
+    // Keys and values follow the map
+    if !args.len().is_multiple_of(2) {
+        return Err(MalError::WrongArity {
+            name: String::from("assoc"),
+            expected: String::from("an odd number of"),
+            actual: args.len() + 1,
+        });
     }
     let result = map.insert(args.iter().cloned())?;
     Ok(Rc::from(result))
@@ -408,18 +472,13 @@ pub fn dissoc(map: &MalHashMap, args: &[Rc<dyn MalType>]) -> MalResult {
 }
 
 #[builtin_func]
-pub fn get(map: &Rc<dyn MalType>, arg: &Rc<dyn MalType>) -> MalResult {
+pub fn get(map: &Rc<dyn MalType>, key: &dyn MalType) -> MalResult {
     if map.is::<MalNil>() {
         return Ok(MalNil::new());
     }
-    let map = map.as_type::<MalHashMap>()?;
-    let key = if let Ok(string) = arg.as_type::<MalString>() {
-        &string.value
-    } else if let Ok(keyword) = arg.as_type::<MalKeyword>() {
-        &keyword.value
-    } else {
-        return Err(MalError::TypeError);
-    };
+    let map = map
+        .as_type::<MalHashMap>()
+        .map_err(|err| err.for_arg("get", 1, map))?;
     if let Some(value) = map.get(key) {
         Ok(value.clone())
     } else {
@@ -428,30 +487,37 @@ pub fn get(map: &Rc<dyn MalType>, arg: &Rc<dyn MalType>) -> MalResult {
 }
 
 #[builtin_func(symbol = "contains?")]
-pub fn contains(map: &MalHashMap, arg: &Rc<dyn MalType>) -> MalResult {
-    let key = if let Ok(string) = arg.as_type::<MalString>() {
-        &string.value
-    } else if let Ok(keyword) = arg.as_type::<MalKeyword>() {
-        &keyword.value
+pub fn contains(collection: &Rc<dyn MalType>, key: &dyn MalType) -> MalResult {
+    let value = if let Ok(map) = collection.as_type::<MalHashMap>() {
+        map.contains(key)
+    } else if let Ok(set) = collection.as_type::<MalSet>() {
+        set.contains(key)
     } else {
-        return Err(MalError::TypeError);
+        let err = MalError::WrongType("hash-map or set");
+        return Err(err.for_arg("contains?", 1, collection));
     };
-    Ok(Rc::from(MalBool::from(map.contains(key))))
+    Ok(Rc::from(MalBool::from(value)))
+}
+
+#[builtin_func(symbol = "hash-set")]
+pub fn hash_set(args: &[Rc<dyn MalType>]) -> MalResult {
+    let set: MalSet = args.iter().collect();
+    Ok(Rc::from(set))
+}
+
+#[builtin_func(symbol = "set?")]
+pub fn is_set(obj: &dyn MalType) -> MalResult {
+    Ok(Rc::from(MalBool::from(obj.is::<MalSet>())))
+}
+
+#[builtin_func]
+pub fn disj(set: &MalSet, args: &[Rc<dyn MalType>]) -> MalResult {
+    Ok(Rc::from(set.remove(args)))
 }
 
 #[builtin_func]
 pub fn keys(map: &MalHashMap) -> MalResult {
-    let list: MalList = map
-        .keys()
-        .map(|s| {
-            let result: Rc<dyn MalType> = if s.starts_with(':') {
-                Rc::from(MalKeyword::from(s.as_str()))
-            } else {
-                Rc::from(MalString::from(s.as_str()))
-            };
-            result
-        })
-        .collect();
+    let list: MalList = map.keys().collect();
     Ok(Rc::from(list))
 }
 
@@ -471,7 +537,7 @@ pub fn readline(prompt: &MalString) -> MalResult {
     let mut buffer = String::new();
     let stdin = io::stdin();
     match stdin.read_line(&mut buffer) {
-        Ok(count) if count == 0 => {
+        Ok(0) => {
             println!();
             Ok(MalNil::new())
         }
@@ -493,16 +559,27 @@ pub fn time_ms() -> MalResult {
 #[builtin_func]
 pub fn conj(collection: &Rc<dyn MalType>, rest: &[Rc<dyn MalType>]) -> MalResult {
     if rest.is_empty() {
-        return Err(MalError::TypeError);
+        return Err(MalError::WrongArity {
+            name: String::from("conj"),
+            expected: String::from("at least 2"),
+            actual: 1,
+        });
+    }
+    if let Ok(set) = collection.as_type::<MalSet>() {
+        return Ok(Rc::from(set.insert(rest.iter().cloned())));
+    }
+    let arr = collection
+        .as_array()
+        .map_err(|err| err.for_arg("conj", 1, collection))?;
+    let mut values = arr.clone();
+    for item in rest {
+        values.push_back(item.clone());
     }
-    let iter = collection.as_array()?.iter().chain(rest.iter());
 
     let result: Rc<dyn MalType> = if collection.is::<MalList>() {
-        let list: MalList = iter.collect();
-        Rc::from(list)
+        Rc::from(MalList::from(values))
     } else {
-        let vector: MalVec = iter.collect();
-        Rc::from(vector)
+        Rc::from(MalVec::from(values))
     };
     Ok(result)
 }
@@ -512,9 +589,45 @@ pub fn is_string(obj: &dyn MalType) -> MalResult {
     Ok(Rc::from(MalBool::from(obj.is::<MalString>())))
 }
 
+#[builtin_func(symbol = "char?")]
+pub fn is_char(obj: &dyn MalType) -> MalResult {
+    Ok(Rc::from(MalBool::from(obj.is::<MalChar>())))
+}
+
+#[builtin_func(name = "char")]
+pub fn char_fn(value: &Rc<dyn MalType>) -> MalResult {
+    if value.is::<MalChar>() {
+        Ok(value.clone())
+    } else if let Ok(int) = value.as_type::<MalInt>() {
+        let ch = u32::try_from(int.value()).ok().and_then(char::from_u32);
+        match ch {
+            Some(ch) => Ok(Rc::from(MalChar::from(ch))),
+            None => Err(MalError::InvalidCharCode(int.value())),
+        }
+    } else {
+        Err(MalError::WrongType("character or integer").for_arg("char", 1, value))
+    }
+}
+
+#[builtin_func]
+pub fn int(value: &Rc<dyn MalType>) -> MalResult {
+    if value.is::<MalInt>() {
+        Ok(value.clone())
+    } else if let Ok(ch) = value.as_type::<MalChar>() {
+        Ok(Rc::from(MalInt::from(ch.value() as i64)))
+    } else if let Ok(float) = value.as_type::<MalFloat>() {
+        Ok(Rc::from(MalInt::from(float.value() as i64)))
+    } else {
+        let err = MalError::WrongType("character, integer or float");
+        Err(err.for_arg("int", 1, value))
+    }
+}
+
 #[builtin_func(symbol = "number?")]
 pub fn is_number(obj: &dyn MalType) -> MalResult {
-    Ok(Rc::from(MalBool::from(obj.is::<MalInt>())))
+    Ok(Rc::from(MalBool::from(
+        obj.is::<MalInt>() || obj.is::<MalBigInt>() || obj.is::<MalFloat>(),
+    )))
 }
 
 #[builtin_func(symbol = "fn?")]
@@ -535,31 +648,63 @@ pub fn is_macro(obj: &dyn MalType) -> MalResult {
 
 #[builtin_func]
 pub fn seq(obj: &Rc<dyn MalType>) -> MalResult {
-    if let Ok(vector) = obj.as_type::<MalVec>() {
-        let list: MalList = vector.values().iter().collect();
-        Ok(Rc::from(list))
-    } else if let Ok(string) = obj.as_type::<MalString>() {
-        let list: MalList = string
-            .value
-            .chars()
-            .map(|ch| Rc::from(MalString::from(ch)) as Rc<dyn MalType>)
-            .collect();
-        Ok(Rc::from(list))
-    } else if obj.is::<MalList>() {
-        Ok(obj.clone())
-    } else if obj.is::<MalNil>() {
+    // Only the first cell is realized, so `(if (seq xs) ...)` works for infinite sequences
+    let cons = obj.uncons().map_err(|err| err.for_arg("seq", 1, obj))?;
+    if cons.is_none() {
         Ok(MalNil::new())
+    } else if obj.is::<MalList>() || obj.is::<MalLazySeq>() {
+        Ok(obj.clone())
     } else {
-        Err(MalError::TypeError)
+        let items = seq::to_vector(obj).map_err(|err| err.for_arg("seq", 1, obj))?;
+        Ok(Rc::from(MalList::from(items)))
     }
 }
 
 #[builtin_func]
-pub fn meta(_arg: &Rc<dyn MalType>) -> MalResult {
-    Err(MalError::Unimplemented)
+pub fn meta(obj: &dyn MalType) -> MalResult {
+    let meta = if let Ok(list) = obj.as_type::<MalList>() {
+        list.meta()
+    } else if let Ok(vector) = obj.as_type::<MalVec>() {
+        vector.meta()
+    } else if let Ok(map) = obj.as_type::<MalHashMap>() {
+        map.meta()
+    } else if let Ok(set) = obj.as_type::<MalSet>() {
+        set.meta()
+    } else if let Ok(clojure) = obj.as_type::<MalClojure>() {
+        clojure.meta()
+    } else if let Ok(func) = obj.as_type::<MalFunc>() {
+        func.meta()
+    } else {
+        None
+    };
+    match meta {
+        Some(meta) => Ok(meta.clone()),
+        None => Ok(MalNil::new()),
+    }
 }
 
 #[builtin_func(symbol = "with-meta")]
-pub fn with_meta(_arg: &Rc<dyn MalType>) -> MalResult {
-    Err(MalError::Unimplemented)
+pub fn with_meta(obj: &Rc<dyn MalType>, meta: &Rc<dyn MalType>) -> MalResult {
+    // Setting `nil` metadata removes it
+    let meta = if meta.is::<MalNil>() {
+        None
+    } else {
+        Some(meta.clone())
+    };
+    if let Ok(list) = obj.as_type::<MalList>() {
+        Ok(Rc::from(list.with_meta(meta)))
+    } else if let Ok(vector) = obj.as_type::<MalVec>() {
+        Ok(Rc::from(vector.with_meta(meta)))
+    } else if let Ok(map) = obj.as_type::<MalHashMap>() {
+        Ok(Rc::from(map.with_meta(meta)))
+    } else if let Ok(set) = obj.as_type::<MalSet>() {
+        Ok(Rc::from(set.with_meta(meta)))
+    } else if let Ok(clojure) = obj.as_type::<MalClojure>() {
+        Ok(Rc::from(clojure.with_meta(meta)))
+    } else if let Ok(func) = obj.as_type::<MalFunc>() {
+        Ok(Rc::from(func.with_meta(meta)))
+    } else {
+        let err = MalError::WrongType("list, vector, hash-map, set or function");
+        Err(err.for_arg("with-meta", 1, obj))
+    }
 }
@@ -0,0 +1,145 @@
+//! Binding forms shared by `let*`, `loop*` and `fn*`.
+//!
+//! A binding form is either a symbol, a vector such as `[a [b c] & rest :as all]` taking apart
+//! sequential values, or a map such as `{:keys [x y] :or {y 0} :as m}` taking apart hash-maps.
+
+use std::rc::Rc;
+
+use crate::{
+    env::Env,
+    eval, seq,
+    types::{
+        MalHashMap, MalKeyword, MalLazySeq, MalList, MalNil, MalString, MalSymbol, MalType, MalVec,
+    },
+    MalError,
+};
+
+/// Bind symbols of `pattern` to the matching parts of `value` in `env`.
+pub fn bind(
+    pattern: &Rc<dyn MalType>,
+    value: Rc<dyn MalType>,
+    env: &Rc<Env>,
+) -> Result<(), MalError> {
+    if let Ok(symbol) = pattern.as_type::<MalSymbol>() {
+        env.set(symbol, value);
+        Ok(())
+    } else if pattern.is::<MalVec>() || pattern.is::<MalList>() {
+        bind_seq(pattern.as_array()?, value, env)
+    } else if let Ok(pattern) = pattern.as_type::<MalHashMap>() {
+        bind_map(pattern, value, env)
+    } else {
+        Err(MalError::InvalidBinding(pattern.clone()))
+    }
+}
+
+/// Bind sequential `patterns` to the items of `value`, which is walked one item at a time so that
+/// only as much of a lazy sequence is realized as the patterns need.
+fn bind_seq(
+    patterns: &im_rc::Vector<Rc<dyn MalType>>,
+    value: Rc<dyn MalType>,
+    env: &Rc<Env>,
+) -> Result<(), MalError> {
+    let mut items = value.clone();
+    let mut patterns = patterns.iter();
+    while let Some(pattern) = patterns.next() {
+        if pattern.is_special("&") {
+            let rest = match patterns.next() {
+                Some(rest) => rest,
+                None => return Err(MalError::InvalidBinding(pattern.clone())),
+            };
+            // Lazy sequences are bound as they are, the rest of other values is a list
+            let items = if items.is::<MalLazySeq>() {
+                items.clone()
+            } else {
+                Rc::from(MalList::from(seq::to_vector(&items)?))
+            };
+            bind(rest, items, env)?;
+        } else if is_keyword(pattern, ":as") {
+            match patterns.next() {
+                Some(all) => bind(all, value.clone(), env)?,
+                None => return Err(MalError::InvalidBinding(pattern.clone())),
+            }
+        } else {
+            match items.uncons()? {
+                Some((first, rest)) => {
+                    bind(pattern, first, env)?;
+                    items = rest;
+                }
+                None => bind(pattern, MalNil::new(), env)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn bind_map(pattern: &MalHashMap, value: Rc<dyn MalType>, env: &Rc<Env>) -> Result<(), MalError> {
+    let map = if value.is::<MalNil>() {
+        MalHashMap::new()
+    } else {
+        match value.as_type::<MalHashMap>() {
+            Ok(map) => map.clone(),
+            Err(_) => return Err(MalError::WrongType("hash-map")),
+        }
+    };
+    let defaults = match pattern.get(&MalKeyword::from(":or")) {
+        Some(defaults) => match defaults.as_type::<MalHashMap>() {
+            Ok(defaults) => Some(defaults),
+            Err(_) => return Err(MalError::InvalidBinding(defaults.clone())),
+        },
+        None => None,
+    };
+    for (key, lookup) in pattern.iter() {
+        if is_keyword(key, ":or") {
+            continue;
+        } else if is_keyword(key, ":as") {
+            bind(lookup, value.clone(), env)?;
+        } else if is_keyword(key, ":keys") || is_keyword(key, ":strs") || is_keyword(key, ":syms") {
+            let symbols = lookup
+                .as_array()
+                .map_err(|_| MalError::InvalidBinding(lookup.clone()))?;
+            for symbol in symbols {
+                let name = match symbol.as_type::<MalSymbol>() {
+                    Ok(name) => name.as_str(),
+                    Err(_) => return Err(MalError::InvalidBinding(symbol.clone())),
+                };
+                let map_key: Rc<dyn MalType> = if is_keyword(key, ":keys") {
+                    Rc::from(MalKeyword::from(format!(":{}", name)))
+                } else if is_keyword(key, ":strs") {
+                    Rc::from(MalString::from(name))
+                } else {
+                    symbol.clone()
+                };
+                let found = map.get(map_key.as_ref()).cloned();
+                bind(symbol, or_default(symbol, found, defaults, env)?, env)?;
+            }
+        } else {
+            let found = map.get(lookup.as_ref()).cloned();
+            bind(key, or_default(key, found, defaults, env)?, env)?;
+        }
+    }
+    Ok(())
+}
+
+/// Value found in the map, or the `:or` default of the symbol evaluated in `env` when the key
+/// is missing.
+fn or_default(
+    pattern: &Rc<dyn MalType>,
+    found: Option<Rc<dyn MalType>>,
+    defaults: Option<&MalHashMap>,
+    env: &Rc<Env>,
+) -> Result<Rc<dyn MalType>, MalError> {
+    if let Some(found) = found {
+        return Ok(found);
+    }
+    match defaults.and_then(|defaults| defaults.get(pattern.as_ref())) {
+        Some(default) => eval(default.clone(), env),
+        None => Ok(MalNil::new()),
+    }
+}
+
+fn is_keyword(value: &Rc<dyn MalType>, name: &str) -> bool {
+    match value.as_type::<MalKeyword>() {
+        Ok(keyword) => keyword.value == name,
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,200 @@
+//! Conversions between MAL values and Rust values for applications embedding the interpreter.
+//!
+//! Host applications define globals and functions with `Env::set_global` and
+//! `Env::register_fn`, evaluate code with `eval_str` and read results back with
+//! `Env::get_global` and `Env::call`.
+
+use std::rc::Rc;
+
+use crate::{
+    env::Env,
+    seq,
+    types::{MalBool, MalFloat, MalInt, MalNil, MalString, MalType, MalVec},
+    MalError,
+};
+
+/// Rust value that can be passed to MAL code.
+pub trait IntoMal {
+    fn into_mal(self) -> Rc<dyn MalType>;
+}
+
+/// Rust value that can be read back from a MAL value.
+pub trait FromMal: Sized {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError>;
+}
+
+/// Arguments of a MAL function called from Rust, tuples of values converted with `IntoMal`.
+pub trait IntoMalArgs {
+    fn into_mal_args(self) -> Vec<Rc<dyn MalType>>;
+}
+
+impl IntoMal for Rc<dyn MalType> {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        self
+    }
+}
+
+impl IntoMal for () {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        MalNil::new()
+    }
+}
+
+impl IntoMal for bool {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        Rc::from(MalBool::from(self))
+    }
+}
+
+impl IntoMal for i64 {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        Rc::from(MalInt::from(self))
+    }
+}
+
+impl IntoMal for i32 {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        i64::from(self).into_mal()
+    }
+}
+
+impl IntoMal for f64 {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        Rc::from(MalFloat::from(self))
+    }
+}
+
+impl IntoMal for String {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        Rc::from(MalString::from(self))
+    }
+}
+
+impl IntoMal for &str {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        Rc::from(MalString::from(self))
+    }
+}
+
+impl<T: IntoMal> IntoMal for Option<T> {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        match self {
+            Some(value) => value.into_mal(),
+            None => MalNil::new(),
+        }
+    }
+}
+
+impl<T: IntoMal> IntoMal for Vec<T> {
+    fn into_mal(self) -> Rc<dyn MalType> {
+        let items: MalVec = self.into_iter().map(IntoMal::into_mal).collect();
+        Rc::from(items)
+    }
+}
+
+impl FromMal for Rc<dyn MalType> {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromMal for () {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        value.as_type::<MalNil>().map(|_| ())
+    }
+}
+
+impl FromMal for bool {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        value.as_type::<MalBool>().map(MalBool::value)
+    }
+}
+
+impl FromMal for i64 {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        value.as_type::<MalInt>().map(MalInt::value)
+    }
+}
+
+impl FromMal for f64 {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        // Integers are widened so that `(+ 1 1)` can be read as a float
+        match value.as_type::<MalInt>() {
+            Ok(int) => Ok(int.value() as f64),
+            Err(_) => value.as_type::<MalFloat>().map(MalFloat::value),
+        }
+    }
+}
+
+impl FromMal for String {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        value
+            .as_type::<MalString>()
+            .map(|string| string.as_str().to_owned())
+    }
+}
+
+impl<T: FromMal> FromMal for Option<T> {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        if value.is::<MalNil>() {
+            Ok(None)
+        } else {
+            T::from_mal(value).map(Some)
+        }
+    }
+}
+
+impl<T: FromMal> FromMal for Vec<T> {
+    fn from_mal(value: &Rc<dyn MalType>) -> Result<Self, MalError> {
+        seq::iter(value).map(|item| T::from_mal(&item?)).collect()
+    }
+}
+
+impl IntoMalArgs for Vec<Rc<dyn MalType>> {
+    fn into_mal_args(self) -> Vec<Rc<dyn MalType>> {
+        self
+    }
+}
+
+impl IntoMalArgs for () {
+    fn into_mal_args(self) -> Vec<Rc<dyn MalType>> {
+        Vec::new()
+    }
+}
+
+macro_rules! tuple_args {
+    ($($name:ident),+) => {
+        impl<$($name: IntoMal),+> IntoMalArgs for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn into_mal_args(self) -> Vec<Rc<dyn MalType>> {
+                let ($($name,)+) = self;
+                vec![$($name.into_mal()),+]
+            }
+        }
+    };
+}
+
+tuple_args!(A);
+tuple_args!(A, B);
+tuple_args!(A, B, C);
+tuple_args!(A, B, C, D);
+tuple_args!(A, B, C, D, E);
+
+/// Argument `index` of a function registered with `Env::register_fn` converted to `T`, missing
+/// arguments are read as nil.
+pub fn arg<T: FromMal>(args: &[Rc<dyn MalType>], index: usize) -> Result<T, MalError> {
+    match args.get(index) {
+        Some(value) => T::from_mal(value),
+        None => T::from_mal(&(MalNil::new() as Rc<dyn MalType>)),
+    }
+}
+
+/// Call a MAL function value with `args`, converting the result to `R`.
+pub fn call<A, R>(func: &Rc<dyn MalType>, args: A, env: &Rc<Env>) -> Result<R, MalError>
+where
+    A: IntoMalArgs,
+    R: FromMal,
+{
+    let result = seq::call(func, &args.into_mal_args(), env)?;
+    R::from_mal(&result)
+}
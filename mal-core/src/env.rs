@@ -1,16 +1,78 @@
-use std::{cell::RefCell, collections::HashMap, env, rc::Rc};
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    fmt::Debug,
+    iter,
+    path::PathBuf,
+    rc::Rc,
+};
 
 use crate::{
+    collection::*,
     core::*,
-    rep,
-    types::{func::MalFuncPtr, MalFunc, MalList, MalString, MalSymbol, MalType},
+    embed::{self, FromMal, IntoMal, IntoMalArgs},
+    eval_source,
+    namespace::*,
+    re::*,
+    reader::Source,
+    seq::*,
+    string::*,
+    types::{func::MalFuncPtr, MalFunc, MalList, MalString, MalSymbol, MalType, MalVec},
     MalError, MalResult,
 };
 
+/// Macros and functions written in MAL that every environment starts with.
+const PRELUDE: &str = include_str!("core.mal");
+
+/// Name of the namespace `Env::new` starts in.
+pub const USER_NAMESPACE: &str = "user";
+
 #[derive(Debug)]
 pub struct Env {
     env: RefCell<HashMap<MalSymbol, Rc<dyn MalType>>>,
     outer: Option<Rc<Env>>,
+    namespace: Option<Namespace>,
+    registry: Option<RefCell<Registry>>,
+}
+
+/// Name and aliases of a namespace environment.
+#[derive(Debug)]
+struct Namespace {
+    name: String,
+    aliases: RefCell<HashMap<String, String>>,
+}
+
+/// Namespaces of an interpreter, kept by the root environment with the builtins.
+#[derive(Default)]
+pub struct Registry {
+    namespaces: HashMap<String, Rc<Env>>,
+    current: String,
+    loading: Vec<String>,
+}
+
+impl Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Namespaces refer back to the root environment, only their names are printed
+        f.debug_struct("Registry")
+            .field("namespaces", &self.namespaces.keys().collect::<Vec<_>>())
+            .field("current", &self.current)
+            .field("loading", &self.loading)
+            .finish()
+    }
+}
+
+impl Registry {
+    pub fn get(&self, name: &str) -> Option<&Rc<Env>> {
+        self.namespaces.get(name)
+    }
+
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    pub fn is_loading(&self, name: &str) -> bool {
+        self.loading.iter().any(|loading| loading == name)
+    }
 }
 
 impl Default for Env {
@@ -18,13 +80,20 @@ impl Default for Env {
         Self {
             env: RefCell::from(HashMap::new()),
             outer: None,
+            namespace: None,
+            registry: None,
         }
     }
 }
 
 impl Env {
+    /// Environment of the `user` namespace, builtins are kept in the root environment shared
+    /// by every namespace.
     pub fn new() -> Rc<Self> {
-        let env = Rc::from(Self::default());
+        let env = Rc::from(Self {
+            registry: Some(RefCell::default()),
+            ..Self::default()
+        });
 
         env.set(
             &MalSymbol::from("*host-language*"),
@@ -50,6 +119,7 @@ impl Env {
         env.register(MAL_STR);
         env.register(MAL_READ_STRING);
         env.register(MAL_SLURP);
+        env.register(MAL_LOAD_FILE);
         env.register(MAL_ATOM);
         env.register(MAL_IS_ATOM);
         env.register(MAL_DEREF);
@@ -70,6 +140,7 @@ impl Env {
         env.register(MAL_IS_FALSE);
         env.register(MAL_IS_SYMBOL);
         env.register(MAL_SYMBOL);
+        env.register(MAL_GENSYM);
         env.register(MAL_IS_KEYWORD);
         env.register(MAL_KEYWORD);
         env.register(MAL_VECTOR);
@@ -83,33 +154,101 @@ impl Env {
         env.register(MAL_CONTAINS);
         env.register(MAL_KEYS);
         env.register(MAL_VALS);
+        env.register(MAL_HASH_SET);
+        env.register(MAL_IS_SET);
+        env.register(MAL_DISJ);
         env.register(MAL_READLINE);
         env.register(MAL_TIME_MS);
         env.register(MAL_CONJ);
         env.register(MAL_IS_STRING);
         env.register(MAL_IS_NUMBER);
+        env.register(MAL_IS_CHAR);
+        env.register(MAL_CHAR);
+        env.register(MAL_INT);
         env.register(MAL_IS_FN);
         env.register(MAL_IS_MACRO);
         env.register(MAL_SEQ);
         env.register(MAL_META);
         env.register(MAL_WITH_META);
+        env.register(MAL_RE_PATTERN);
+        env.register(MAL_RE_MATCHER);
+        env.register(MAL_RE_FIND);
+        env.register(MAL_RE_MATCHES);
+        env.register(MAL_RE_SEQ);
+        env.register(MAL_RE_GROUPS);
+        env.register(MAL_ITERATE);
+        env.register(MAL_RANGE);
+        env.register(MAL_REPEAT);
+        env.register(MAL_TAKE);
+        env.register(MAL_DROP);
+        env.register(MAL_TAKE_WHILE);
+        env.register(MAL_FILTER);
+        env.register(MAL_REMOVE);
+        env.register(MAL_REDUCE);
+        env.register(MAL_SORT);
+        env.register(MAL_SORT_BY);
+        env.register(MAL_GROUP_BY);
+        env.register(MAL_FREQUENCIES);
+        env.register(MAL_PARTITION);
+        env.register(MAL_INTERLEAVE);
+        env.register(MAL_DISTINCT);
+        env.register(MAL_SOME);
+        env.register(MAL_EVERY);
+        env.register(MAL_ZIPMAP);
+        env.register(MAL_INTO);
+        env.register(MAL_UPDATE);
+        env.register(MAL_GET_IN);
+        env.register(MAL_ASSOC_IN);
+        env.register(MAL_UPDATE_IN);
+        env.register(MAL_REQUIRE);
+        env.register(MAL_REFER);
+        env.set_load_path(iter::once(PathBuf::from(".")));
 
-        rep("(def! not (fn* (a) (if a false true)))", &env).unwrap();
-        rep(
-            r#"(def! load-file (fn* (f) (eval (read-string (str "(do " (slurp f) "\nnil)")))))"#,
-            &env,
-        )
-        .unwrap();
-        rep(r#"(defmacro! cond (fn* (& xs) (if (> (count xs) 0) (list 'if (first xs) (if (> (count xs) 1) (nth xs 1) (throw "odd number of forms to cond")) (cons 'cond (rest (rest xs)))))))"#, &env).unwrap();
-        env.init_argv();
+        let string = env.create_namespace("str");
+        string.register(MAL_SUBS);
+        string.register(MAL_SPLIT);
+        string.register(MAL_JOIN);
+        string.register(MAL_TRIM);
+        string.register(MAL_UPPER_CASE);
+        string.register(MAL_LOWER_CASE);
+        string.register(MAL_INDEX_OF);
+        string.register(MAL_REPLACE);
+        string.register(MAL_STARTS_WITH);
+        string.register(MAL_ENDS_WITH);
+        string.register(MAL_INCLUDES);
+        string.register(MAL_FORMAT);
 
-        env
+        let prelude = Rc::from(Source::new("core.mal", PRELUDE));
+        eval_source(&prelude, &env).unwrap();
+        env.init_argv(iter::empty());
+
+        let user = env.create_namespace(USER_NAMESPACE);
+        env.set_current_namespace(USER_NAMESPACE);
+        user
+    }
+
+    /// Directories `require` looks for namespace files in, in order.
+    pub fn set_load_path<T>(&self, paths: T)
+    where
+        T: IntoIterator<Item = PathBuf>,
+    {
+        let paths: MalVec = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.to_string_lossy().into_owned();
+                Rc::from(MalString::from(path)) as Rc<dyn MalType>
+            })
+            .collect();
+        self.root()
+            .set(&MalSymbol::from("*load-path*"), Rc::from(paths));
     }
 
-    pub fn init_argv(&self) {
-        let argv: Vec<_> = env::args()
+    pub fn init_argv<T>(&self, args: T)
+    where
+        T: IntoIterator<Item = String>,
+    {
+        let argv: Vec<_> = args
             .into_iter()
-            .skip(2)
             .map(|s| Rc::from(MalString::from(s)) as Rc<dyn MalType>)
             .collect();
 
@@ -118,8 +257,10 @@ impl Env {
         self.set(&symbol, argv);
     }
 
+    /// Value of the symbol, symbols such as `str/join` not defined as is are looked up in the
+    /// namespace or alias before the `/`.
     pub fn get(&self, symbol: &MalSymbol) -> MalResult {
-        match self.get_impl(symbol) {
+        match self.get_impl(symbol).or_else(|| self.get_qualified(symbol)) {
             Some(value) => Ok(value),
             None => {
                 let not_found = Rc::from(symbol.clone());
@@ -138,6 +279,131 @@ impl Env {
         }
     }
 
+    fn get_qualified(&self, symbol: &MalSymbol) -> Option<Rc<dyn MalType>> {
+        let (namespace, name) = symbol.as_str().split_once('/')?;
+        if namespace.is_empty() || name.is_empty() {
+            return None;
+        }
+        let namespace = self.resolve_alias(namespace);
+        let target = self.root().registry()?.get(&namespace)?.clone();
+        let value = target.env.borrow().get(&MalSymbol::from(name)).cloned();
+        value
+    }
+
+    /// Full name of the namespace `alias` refers to in the enclosing namespace.
+    pub fn resolve_alias(&self, alias: &str) -> String {
+        match self.enclosing_namespace() {
+            Some(namespace) => match namespace.aliases.borrow().get(alias) {
+                Some(name) => name.clone(),
+                None => alias.to_string(),
+            },
+            None => alias.to_string(),
+        }
+    }
+
+    fn root(&self) -> &Env {
+        match &self.outer {
+            Some(outer) => outer.root(),
+            None => self,
+        }
+    }
+
+    fn enclosing_namespace(&self) -> Option<&Namespace> {
+        match (&self.namespace, &self.outer) {
+            (Some(namespace), _) => Some(namespace),
+            (None, Some(outer)) => outer.enclosing_namespace(),
+            (None, None) => None,
+        }
+    }
+
+    /// Name of the namespace when this is the environment of one.
+    pub fn namespace_name(&self) -> Option<&str> {
+        self.namespace
+            .as_ref()
+            .map(|namespace| namespace.name.as_str())
+    }
+
+    /// Let the enclosing namespace refer to namespace `name` by `alias`.
+    pub fn add_alias(&self, alias: &str, name: &str) {
+        if let Some(namespace) = self.enclosing_namespace() {
+            namespace
+                .aliases
+                .borrow_mut()
+                .insert(alias.to_string(), name.to_string());
+        }
+    }
+
+    /// Symbols defined directly in this environment with their values.
+    pub fn definitions(&self) -> Vec<(MalSymbol, Rc<dyn MalType>)> {
+        self.env
+            .borrow()
+            .iter()
+            .map(|(symbol, value)| (symbol.clone(), value.clone()))
+            .collect()
+    }
+
+    pub fn registry(&self) -> Option<Ref<'_, Registry>> {
+        self.registry.as_ref().map(|registry| registry.borrow())
+    }
+
+    /// Environment of namespace `name`, created in the registry of the root environment when
+    /// it doesn't exist yet.
+    pub fn create_namespace(self: &Rc<Self>, name: &str) -> Rc<Env> {
+        let root = global(self);
+        if let Some(env) = root
+            .registry()
+            .and_then(|registry| registry.get(name).cloned())
+        {
+            return env;
+        }
+        let env = Rc::from(Self {
+            outer: Some(root.clone()),
+            namespace: Some(Namespace {
+                name: name.to_string(),
+                aliases: RefCell::default(),
+            }),
+            ..Self::default()
+        });
+        if let Some(registry) = &root.registry {
+            registry
+                .borrow_mut()
+                .namespaces
+                .insert(name.to_string(), env.clone());
+        }
+        env
+    }
+
+    /// Forget namespace `name`, used when loading it fails.
+    pub fn remove_namespace(&self, name: &str) {
+        if let Some(registry) = &self.root().registry {
+            registry.borrow_mut().namespaces.remove(name);
+        }
+    }
+
+    /// Make `name` the namespace new top level forms are evaluated in, returning the previous
+    /// one.
+    pub fn set_current_namespace(&self, name: &str) -> String {
+        match &self.root().registry {
+            Some(registry) => {
+                let mut registry = registry.borrow_mut();
+                std::mem::replace(&mut registry.current, name.to_string())
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Mark namespace `name` as being loaded, or unmark it when `loading` is false.
+    pub fn set_loading(&self, name: &str, loading: bool) {
+        if let Some(registry) = &self.root().registry {
+            let mut registry = registry.borrow_mut();
+            if loading {
+                registry.loading.push(name.to_string());
+            } else {
+                registry.loading.retain(|other| other != name);
+            }
+        }
+    }
+
     pub fn set(&self, symbol: &MalSymbol, value: Rc<dyn MalType>) {
         // FIXME: Find a way to avoid allocation
         self.env.borrow_mut().insert(symbol.clone(), value);
@@ -149,7 +415,11 @@ impl Env {
         values: &[Rc<dyn MalType>],
     ) -> Result<(), MalError> {
         if symbols.len() != values.len() {
-            return Err(MalError::TypeError);
+            return Err(MalError::WrongArity {
+                name: String::from("fn*"),
+                expected: symbols.len().to_string(),
+                actual: values.len(),
+            });
         }
         for (symbol, value) in symbols.iter().zip(values) {
             let symbol: &MalSymbol = symbol.as_type()?;
@@ -165,31 +435,91 @@ impl Env {
     }
 
     fn register_func(&self, name: &'static str, ptr: &'static MalFuncPtr) {
-        let symbol = MalSymbol::from(name);
+        // Builtins of a namespace such as `str/join` are defined without the namespace
+        let local = match self.namespace_name() {
+            Some(namespace) => name
+                .strip_prefix(namespace)
+                .and_then(|name| name.strip_prefix('/'))
+                .unwrap_or(name),
+            None => name,
+        };
+        let symbol = MalSymbol::from(local);
         let func = Rc::from(MalFunc::new(name, ptr));
         self.env.borrow_mut().insert(symbol, func);
     }
 
+    /// Define `name` in the namespace of this environment as a function calling `func`, which
+    /// can capture state of the host application.
+    pub fn register_fn<F>(self: &Rc<Self>, name: &str, func: F)
+    where
+        F: Fn(&[Rc<dyn MalType>], &Rc<Env>) -> MalResult + 'static,
+    {
+        let func = Rc::from(MalFunc::from_closure(name, func));
+        namespace_of(self).set(&MalSymbol::from(name), func);
+    }
+
+    /// Define `name` in the namespace of this environment, as `def!` does.
+    pub fn set_global<T: IntoMal>(self: &Rc<Self>, name: &str, value: T) {
+        namespace_of(self).set(&MalSymbol::from(name), value.into_mal());
+    }
+
+    /// Value of `name` converted to `T`.
+    pub fn get_global<T: FromMal>(&self, name: &str) -> Result<T, MalError> {
+        T::from_mal(&self.get(&MalSymbol::from(name))?)
+    }
+
+    /// Call the function `name` with `args`, converting the result to `R`.
+    pub fn call<A, R>(self: &Rc<Self>, name: &str, args: A) -> Result<R, MalError>
+    where
+        A: IntoMalArgs,
+        R: FromMal,
+    {
+        let func = self.get(&MalSymbol::from(name))?;
+        embed::call(&func, args, self)
+    }
+
     pub fn with_outer(outer: Rc<Self>) -> Rc<Self> {
         Rc::from(Self {
-            env: RefCell::from(HashMap::new()),
             outer: Some(outer),
+            ..Self::default()
         })
     }
 
+    /// Symbols visible from this environment starting with `start`.
     pub fn starts_with(&self, start: &str) -> Vec<String> {
-        self.env
+        let mut symbols: Vec<_> = self
+            .env
             .borrow()
             .keys()
             .filter(|symbol| symbol.starts_with(start))
             .map(|symbol| symbol.to_string())
-            .collect()
+            .collect();
+        if let Some(outer) = &self.outer {
+            symbols.extend(outer.starts_with(start));
+        }
+        symbols
     }
 }
 
+/// Root environment with the builtins.
 pub fn global(env: &Rc<Env>) -> &Rc<Env> {
     match &env.outer {
         Some(outer) => global(outer),
         None => env,
     }
 }
+
+/// Innermost environment of a namespace enclosing `env`, or the root environment outside of
+/// namespaces.
+pub fn namespace_of(env: &Rc<Env>) -> &Rc<Env> {
+    match (&env.namespace, &env.outer) {
+        (None, Some(outer)) => namespace_of(outer),
+        _ => env,
+    }
+}
+
+/// Environment of the namespace top level forms are currently evaluated in.
+pub fn current_namespace(env: &Rc<Env>) -> Option<Rc<Env>> {
+    let registry = global(env).registry()?;
+    registry.get(registry.current()).cloned()
+}
@@ -2,23 +2,36 @@
 #![deny(clippy::all)]
 
 use std::{
-    collections::HashMap,
     mem::{self, MaybeUninit},
     rc::Rc,
 };
 
 use env::Env;
+use im_rc::HashMap;
 use mal_derive::builtin_func;
-use reader::{Reader, ReaderResult};
+use namespace::mal_ns;
+use reader::{Location, ParseError, Reader, ReaderResult, Source};
+use seq::mal_lazy_seq;
 use thiserror::Error;
+use trace::{Frame, StackTrace};
 use types::{
-    MalClojure, MalException, MalFunc, MalHashMap, MalList, MalNil, MalSymbol, MalType, MalVec,
+    clojure::Arity, MalClojure, MalException, MalFunc, MalHashMap, MalList, MalNil, MalSet,
+    MalString, MalSymbol, MalType, MalVec,
 };
 
+pub mod collection;
 pub mod core;
+pub mod destructure;
+pub mod embed;
 pub mod env;
+pub mod namespace;
+pub mod re;
 pub mod reader;
+pub mod seq;
+pub mod string;
+pub mod trace;
 pub mod types;
+pub mod value;
 
 pub type MalResult = Result<Rc<dyn MalType>, MalError>;
 
@@ -30,19 +43,144 @@ pub enum MalError {
     NotFound(Rc<dyn MalType>),
     #[error("Exception `{0}`")]
     Exception(Rc<dyn MalType>),
-    #[error("Type error")]
-    TypeError,
+    #[error("Expected {0}")]
+    WrongType(&'static str),
+    #[error("`{builtin}`: argument {position} expected {expected}, got {actual:?}")]
+    ArgType {
+        builtin: &'static str,
+        position: usize,
+        expected: &'static str,
+        actual: Rc<dyn MalType>,
+    },
     #[error("Not implemented!")]
     Unimplemented,
     #[error("IOError")]
     IOError,
+    #[error("Division by zero")]
+    DivisionByZero,
     #[error("{idx} is out of bounds, index should be between 0 and {len}")]
     OutOfBounds { idx: usize, len: usize },
+    #[error("{0} is out of bounds, index can't be negative")]
+    NegativeIndex(i64),
+    #[error("Start index {start} is after end index {end}")]
+    InvalidRange { start: usize, end: usize },
+    #[error("{0} is not a valid character code")]
+    InvalidCharCode(i64),
+    #[error("Invalid format string: {0}")]
+    InvalidFormat(String),
+    #[error("Invalid regular expression: {0}")]
+    InvalidRegex(String),
+    #[error("No match found")]
+    NoMatch,
+    #[error("`recur` can only be used in tail position of `loop*` or `fn*`")]
+    RecurNotInTail,
+    #[error("`{name}` expected {expected} {}, got {actual}", arguments_noun(.expected))]
+    WrongArity {
+        name: String,
+        expected: String,
+        actual: usize,
+    },
+    #[error("`{0:?}` is not a valid binding form")]
+    InvalidBinding(Rc<dyn MalType>),
+    #[error("Invalid function definition: {0}")]
+    InvalidFn(&'static str),
+    #[error("`{0:?}` and `{1:?}` can't be compared")]
+    NotComparable(Rc<dyn MalType>, Rc<dyn MalType>),
+    #[error("Namespace `{0}` not found in the load path")]
+    NamespaceNotFound(String),
+    #[error("Namespace `{0}` is required while it's being loaded")]
+    CircularRequire(String),
+    #[error("`{0:?}` is not a valid require form")]
+    InvalidRequire(Rc<dyn MalType>),
+    #[error("Conversion failed: {0}")]
+    Conversion(String),
+    #[error("{0}")]
+    Parse(ParseError),
+    #[error("{1}: {0}")]
+    Located(Box<MalError>, Location),
+    #[error("{0}")]
+    Traced(Box<MalError>, StackTrace),
+}
+
+/// Noun for an arity description, singular only when a single argument is expected.
+fn arguments_noun(expected: &str) -> &'static str {
+    if expected == "1" || expected == "at least 1" {
+        "argument"
+    } else {
+        "arguments"
+    }
+}
+
+impl MalError {
+    pub fn from_parse(err: ParseError, source: &Rc<Source>) -> Self {
+        let location = Location::new(source.clone(), err.span());
+        MalError::Parse(err).at(location)
+    }
+
+    /// Attach location of the form that raised the error, errors that already
+    /// have a location keep the innermost one.
+    pub fn at(self, location: Location) -> Self {
+        match self.location() {
+            Some(_) => self,
+            None => MalError::Located(Box::from(self), location),
+        }
+    }
+
+    /// Report wrong type of the builtin argument, `position` starts from 1.
+    pub fn for_arg(self, builtin: &'static str, position: usize, actual: &Rc<dyn MalType>) -> Self {
+        match self {
+            MalError::WrongType(expected) => MalError::ArgType {
+                builtin,
+                position,
+                expected,
+                actual: actual.clone(),
+            },
+            _ => self,
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            MalError::Located(_, location) => Some(location),
+            MalError::Traced(err, _) => err.location(),
+            _ => None,
+        }
+    }
+
+    /// Attach the current call stack, errors that already have a stack trace
+    /// keep the one captured closest to where they were raised.
+    pub fn traced(self) -> Self {
+        if self.stack_trace().is_some() {
+            return self;
+        }
+        let stack_trace = StackTrace::capture();
+        if stack_trace.is_empty() {
+            self
+        } else {
+            MalError::Traced(Box::from(self), stack_trace)
+        }
+    }
+
+    pub fn stack_trace(&self) -> Option<&StackTrace> {
+        match self {
+            MalError::Located(err, _) => err.stack_trace(),
+            MalError::Traced(_, stack_trace) => Some(stack_trace),
+            _ => None,
+        }
+    }
+
+    /// Error without the location and stack trace information.
+    pub fn inner(&self) -> &MalError {
+        match self {
+            MalError::Located(err, _) | MalError::Traced(err, _) => err.inner(),
+            _ => self,
+        }
+    }
 }
 
 impl PartialEq for MalError {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
+        match (self.inner(), other.inner()) {
             (Self::NotCallable(l0), Self::NotCallable(r0)) => l0 == r0,
             (Self::NotFound(l0), Self::NotFound(r0)) => l0 == r0,
             (Self::Exception(l0), Self::Exception(r0)) => l0 == r0,
@@ -56,95 +194,178 @@ impl PartialEq for MalError {
                     len: r_len,
                 },
             ) => l_idx == r_idx && l_len == r_len,
-            _ => mem::discriminant(self) == mem::discriminant(other),
+            (Self::WrongType(l0), Self::WrongType(r0)) => l0 == r0,
+            (
+                Self::ArgType {
+                    builtin: l_builtin,
+                    position: l_position,
+                    expected: l_expected,
+                    actual: l_actual,
+                },
+                Self::ArgType {
+                    builtin: r_builtin,
+                    position: r_position,
+                    expected: r_expected,
+                    actual: r_actual,
+                },
+            ) => {
+                l_builtin == r_builtin
+                    && l_position == r_position
+                    && l_expected == r_expected
+                    && l_actual == r_actual
+            }
+            (Self::Parse(l0), Self::Parse(r0)) => l0 == r0,
+            (lhs, rhs) => mem::discriminant(lhs) == mem::discriminant(rhs),
         }
     }
 }
 
 pub fn rep(input: &str, env: &Rc<Env>) -> Result<String, MalError> {
-    let ast = match read(input) {
+    let source = Rc::from(Source::from(input));
+    let ast = match read_source(&source) {
         Ok(ast) => ast,
-        Err(_) => todo!(),
+        Err(err) => return Err(MalError::from_parse(err, &source)),
     };
     let result = eval(ast, env)?;
+    // Lazy sequences are realized before printing so that their errors are reported
+    seq::realize(&result)?;
     Ok(print(result))
 }
 
+/// Evaluate every form of the source in order, returning value of the last one. Forms following
+/// an `ns` form are evaluated in the namespace it switched to.
+pub fn eval_source(source: &Rc<Source>, env: &Rc<Env>) -> MalResult {
+    let mut env = env.clone();
+    let mut reader = Reader::from(source);
+    let mut result: Rc<dyn MalType> = MalNil::new();
+    loop {
+        if let Err(err) = reader.skip_discarded() {
+            return Err(MalError::from_parse(err, source));
+        }
+        if reader.peek().is_none() {
+            break;
+        }
+        result = match reader.read_form() {
+            Ok(ast) => eval(ast, &env)?,
+            Err(err) => return Err(MalError::from_parse(err, source)),
+        };
+        if let Some(namespace) = env::current_namespace(&env) {
+            if !Rc::ptr_eq(env::namespace_of(&env), &namespace) {
+                env = namespace;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Evaluate every form of `input` in `env`, returning the value of the last one.
+pub fn eval_str(input: &str, env: &Rc<Env>) -> MalResult {
+    eval_source(&Rc::from(Source::new("<eval>", input)), env)
+}
+
 pub fn read(input: &str) -> ReaderResult {
-    let mut reader = Reader::from(input).peekable();
-    Reader::read_from(&mut reader)
+    Reader::from(input).read_form()
 }
 
-pub fn eval(mut ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+pub fn read_source(source: &Rc<Source>) -> ReaderResult {
+    Reader::from(source).read_form()
+}
+
+enum Step {
+    Return(Rc<dyn MalType>),
+    Eval(Rc<dyn MalType>),
+    EvalIn(Rc<dyn MalType>, Rc<Env>),
+    Call(Rc<dyn MalType>, Rc<Env>, Frame, RecurTarget),
+    Loop(Rc<dyn MalType>, Rc<Env>, RecurTarget),
+    Recur(Vec<Rc<dyn MalType>>),
+}
+
+/// Innermost `loop*` or function call `recur` jumps back to.
+#[derive(Debug)]
+pub struct RecurTarget {
+    arity: Rc<Arity>,
+    outer: Rc<Env>,
+}
+
+impl RecurTarget {
+    /// Body to evaluate next and the environment with `values` bound in place of the previous
+    /// bindings.
+    fn rebind(&self, values: &[Rc<dyn MalType>]) -> Result<(Rc<dyn MalType>, Rc<Env>), MalError> {
+        let new_env = self.arity.rebind(values, &self.outer)?;
+        Ok((self.arity.body(), new_env))
+    }
+}
+
+pub fn eval(ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    eval_in_target(ast, env, None)
+}
+
+/// Evaluate `ast` with `recur` jumping back to `recur_target`. Forms evaluated by this loop are
+/// in tail position of the innermost target, any other form is evaluated by a nested `eval`
+/// without one, so `recur` outside of tail position is rejected when it's reached.
+pub(crate) fn eval_in_target(
+    mut ast: Rc<dyn MalType>,
+    env: &Rc<Env>,
+    mut recur_target: Option<RecurTarget>,
+) -> MalResult {
     let mut init = false;
+    let mut in_call = false;
     let mut outer: MaybeUninit<Rc<Env>> = MaybeUninit::uninit();
     let mut env = env;
     let result = loop {
-        ast = macro_expand(ast, env)?;
-        if let Ok(list) = ast.as_type::<MalList>() {
-            if list.is_empty() {
-                break Ok(ast);
-            } else if list.is_special("def!") {
-                break mal_def(&list.values()[1..], env);
-            } else if list.is_special("defmacro!") {
-                break mal_defmacro(&list.values()[1..], env);
-            } else if list.is_special("let*") {
-                let (new_ast, new_env) = mal_let(&list.values()[1..], env)?;
+        let form = ast.clone();
+        let new_env = match eval_step(ast, env, recur_target.is_some()) {
+            Ok(Step::Return(value)) => break Ok(value),
+            Ok(Step::Eval(new_ast)) => {
                 ast = new_ast;
-                unsafe {
-                    if init {
-                        let to_drop = outer.assume_init();
-                        drop(to_drop);
-                    } else {
-                        init = true;
-                    }
-                    outer = MaybeUninit::uninit();
-                    outer.write(new_env);
-                    env = outer.assume_init_ref();
-                }
-            } else if list.is_special("macroexpand") {
-                if let Some(ast) = list.get(1) {
-                    break macro_expand(ast.clone(), env);
+                continue;
+            }
+            Ok(Step::EvalIn(new_ast, new_env)) => {
+                ast = new_ast;
+                new_env
+            }
+            Ok(Step::Call(new_ast, new_env, frame, target)) => {
+                // Tail call replaces frame of the function it was made from
+                if in_call {
+                    trace::replace(frame);
                 } else {
-                    return Err(MalError::TypeError);
+                    trace::push(frame);
+                    in_call = true;
                 }
-            } else if list.is_special("do") {
-                ast = mal_do(&list.values()[1..], env)?;
-            } else if list.is_special("if") {
-                ast = mal_if(&list.values()[1..], env)?;
-            } else if list.is_special("fn*") {
-                break mal_fn(&list.values()[1..], env);
-            } else if list.is_special("quote") {
-                break mal_quote(&list.values()[1..], env);
-            } else if list.is_special("quasiquote") {
-                break mal_quasiquote(&list.values()[1..], env);
-            } else if list.is_special("try*") {
-                break mal_try(&list.values()[1..], env);
-            } else {
-                let new_list = eval_ast(ast, env)?;
-                let values = new_list.as_type::<MalList>()?.values();
-                if let Ok(func) = values[0].as_type::<MalFunc>() {
-                    break func.call(&values[1..], env);
-                } else if let Ok(clojure) = values[0].as_type::<MalClojure>() {
-                    let (new_ast, new_env) = clojure.call(&values[1..], env)?;
-                    ast = new_ast;
-                    unsafe {
-                        if init {
-                            let to_drop = outer.assume_init();
-                            drop(to_drop);
-                        } else {
-                            init = true;
-                        }
-                        outer = MaybeUninit::uninit();
-                        outer.write(new_env);
-                        env = outer.assume_init_ref();
+                recur_target = Some(target);
+                ast = new_ast;
+                new_env
+            }
+            Ok(Step::Loop(new_ast, new_env, target)) => {
+                recur_target = Some(target);
+                ast = new_ast;
+                new_env
+            }
+            Ok(Step::Recur(values)) => {
+                let rebound = match &recur_target {
+                    Some(target) => target.rebind(&values),
+                    None => Err(MalError::RecurNotInTail),
+                };
+                match rebound {
+                    Ok((new_ast, new_env)) => {
+                        ast = new_ast;
+                        new_env
                     }
-                } else {
-                    break Err(MalError::NotCallable(values[0].clone()));
+                    Err(err) => break Err(at_form(err, &form)),
                 }
             }
-        } else {
-            break eval_ast(ast, env);
+            Err(err) => break Err(at_form(err, &form)),
+        };
+        unsafe {
+            if init {
+                let to_drop = outer.assume_init();
+                drop(to_drop);
+            } else {
+                init = true;
+            }
+            outer = MaybeUninit::uninit();
+            outer.write(new_env);
+            env = outer.assume_init_ref();
         }
     };
     if init {
@@ -153,7 +374,99 @@ pub fn eval(mut ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
             drop(env);
         }
     }
-    result
+    if in_call {
+        let result = result.map_err(MalError::traced);
+        trace::pop();
+        result
+    } else {
+        result
+    }
+}
+
+/// Attach location of the list form that raised the error.
+fn at_form(err: MalError, form: &Rc<dyn MalType>) -> MalError {
+    match form.as_type::<MalList>().map(|list| list.location()) {
+        Ok(Some(location)) => err.at(location.clone()),
+        _ => err,
+    }
+}
+
+/// Evaluate one step of `ast`, `tail` is set when it's in tail position of a `loop*` or `fn*`.
+fn eval_step(ast: Rc<dyn MalType>, env: &Rc<Env>, tail: bool) -> Result<Step, MalError> {
+    let ast = macro_expand(ast, env)?;
+    let list = match ast.as_type::<MalList>() {
+        Ok(list) => list,
+        Err(_) => return Ok(Step::Return(eval_ast(ast, env)?)),
+    };
+    if list.is_empty() {
+        return Ok(Step::Return(ast));
+    }
+    let args = list.rest();
+    if list.is_special("def!") {
+        Ok(Step::Return(mal_def(&args, env)?))
+    } else if list.is_special("defmacro!") {
+        Ok(Step::Return(mal_defmacro(&args, env)?))
+    } else if list.is_special("let*") {
+        let (new_ast, new_env) = mal_let(&args, env)?;
+        Ok(Step::EvalIn(new_ast, new_env))
+    } else if list.is_special("loop*") {
+        let (new_env, target) = mal_loop(&args, env)?;
+        Ok(Step::Loop(args[1].clone(), new_env, target))
+    } else if list.is_special("recur") {
+        if !tail {
+            return Err(MalError::RecurNotInTail);
+        }
+        let values = args
+            .iter()
+            .map(|arg| eval(arg.clone(), env))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Step::Recur(values))
+    } else if list.is_special("macroexpand") {
+        match list.get(1) {
+            Some(ast) => Ok(Step::Return(macro_expand(ast.clone(), env)?)),
+            None => Err(MalError::WrongArity {
+                name: String::from("macroexpand"),
+                expected: String::from("1"),
+                actual: 0,
+            }),
+        }
+    } else if list.is_special("do") {
+        Ok(Step::Eval(mal_do(&args, env)?))
+    } else if list.is_special("if") {
+        Ok(Step::Eval(mal_if(&args, env)?))
+    } else if list.is_special("fn*") {
+        Ok(Step::Return(mal_fn(&args, env)?))
+    } else if list.is_special("quote") {
+        Ok(Step::Return(mal_quote(&args, env)?))
+    } else if list.is_special("quasiquote") {
+        Ok(Step::Return(mal_quasiquote(&args, env)?))
+    } else if list.is_special("lazy-seq") {
+        Ok(Step::Return(mal_lazy_seq(&args, env)?))
+    } else if list.is_special("ns") {
+        Ok(Step::Return(mal_ns(&args, env)?))
+    } else if list.is_special("try*") {
+        Ok(Step::Return(mal_try(&args, env)?))
+    } else {
+        let values = list
+            .iter()
+            .map(|item| eval(item.clone(), env))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Ok(func) = values[0].as_type::<MalFunc>() {
+            Ok(Step::Return(func.call(&values[1..], env)?))
+        } else if let Ok(clojure) = values[0].as_type::<MalClojure>() {
+            let args = &values[1..];
+            let arity = clojure.arity(args.len())?;
+            let new_env = arity.bind(args, clojure.outer())?;
+            let frame = Frame::new(clojure.name(), list.location().cloned());
+            let target = RecurTarget {
+                arity: arity.clone(),
+                outer: clojure.outer().clone(),
+            };
+            Ok(Step::Call(arity.body(), new_env, frame, target))
+        } else {
+            Err(MalError::NotCallable(values[0].clone()))
+        }
+    }
 }
 
 pub fn print(input: Rc<dyn MalType>) -> String {
@@ -174,11 +487,18 @@ pub fn eval_ast(ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
         }
         Ok(Rc::from(MalVec::from(result)))
     } else if let Ok(map) = ast.as_type::<MalHashMap>() {
-        let mut result = HashMap::with_capacity(map.len());
+        let mut result = HashMap::new();
         for (key, value) in map.iter() {
-            result.insert(key.to_string(), eval(value.clone(), env)?);
+            result.insert(key.clone(), eval(value.clone(), env)?);
         }
         Ok(Rc::from(MalHashMap::from(result)))
+    } else if let Ok(set) = ast.as_type::<MalSet>() {
+        let mut result = Vec::with_capacity(set.len());
+        for item in set.iter() {
+            result.push(eval(item.clone(), env)?)
+        }
+        let set: MalSet = result.into_iter().collect();
+        Ok(Rc::from(set))
     } else if let Ok(symbol) = ast.as_type() {
         env.get(symbol)
     } else {
@@ -189,6 +509,9 @@ pub fn eval_ast(ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
 #[builtin_func(name = "def", symbol = "def!", special)]
 pub fn def_fn(symbol: &MalSymbol, ast: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
     let value = eval(ast.clone(), env)?;
+    if let Ok(clojure) = value.as_type::<MalClojure>() {
+        clojure.set_name(symbol);
+    }
     env.set(symbol, value.clone());
     Ok(value)
 }
@@ -199,25 +522,43 @@ pub fn let_fn(
     ast: &Rc<dyn MalType>,
     env: &Rc<Env>,
 ) -> Result<(Rc<dyn MalType>, Rc<Env>), MalError> {
-    let env_list = bindings.as_array()?;
+    let env_list = bindings
+        .as_array()
+        .map_err(|err| err.for_arg("let*", 1, bindings))?;
     if env_list.len() % 2 != 0 {
-        return Err(MalError::TypeError);
+        let err = MalError::WrongType("even number of binding forms");
+        return Err(err.for_arg("let*", 1, bindings));
     }
 
     let new_env = Env::with_outer(env.clone());
     let pair_count = env_list.len() / 2;
     for i in 0..pair_count {
-        let symbol = env_list[2 * i].as_type()?;
         let value = eval(env_list[2 * i + 1].clone(), &new_env)?;
-        new_env.set(symbol, value.clone());
+        destructure::bind(&env_list[2 * i], value, &new_env)?;
     }
     Ok((ast.clone(), new_env))
 }
 
+#[builtin_func(name = "loop", symbol = "loop*", special)]
+pub fn loop_fn(
+    bindings: &Rc<dyn MalType>,
+    body: &Rc<dyn MalType>,
+    env: &Rc<Env>,
+) -> Result<(Rc<Env>, RecurTarget), MalError> {
+    let (body, new_env) = let_fn(bindings, body, env)?;
+    let patterns = bindings.as_array()?.iter().step_by(2).cloned().collect();
+    let target = RecurTarget {
+        arity: Rc::from(Arity::try_new(&patterns, body)?),
+        outer: env.clone(),
+    };
+    Ok((new_env, target))
+}
+
 #[builtin_func(name = "do", special)]
 pub fn do_fn(args: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    // `(do)` is nil, so macros can splice an empty body into it
     if args.is_empty() {
-        return Err(MalError::TypeError);
+        return Ok(MalNil::new());
     }
     let len = args.len();
     for arg in &args[0..len - 1] {
@@ -242,9 +583,47 @@ pub fn if_fn(
     }
 }
 
+/// Either `(fn* [params] body)` or `(fn* ([params] body) ([params] body) ..)` with an overload
+/// for each number of arguments.
 #[builtin_func(name = "fn", symbol = "fn*", special)]
-pub fn fn_fn(names: &Rc<dyn MalType>, body: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
-    MalClojure::try_new(names.as_array()?, body.clone(), env.clone())
+pub fn fn_fn(args: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    let is_overload = |arg: &Rc<dyn MalType>| match arg.as_type::<MalList>() {
+        Ok(list) => matches!(list.get(0), Some(params) if params.is::<MalVec>()),
+        Err(_) => false,
+    };
+    let mut arities = Vec::with_capacity(args.len());
+    if !args.is_empty() && args.iter().all(is_overload) {
+        for arg in args {
+            let overload = arg.as_array()?;
+            match (overload.get(0), overload.get(1), overload.len()) {
+                (Some(params), Some(body), 2) => arities.push(fn_arity(params, body)?),
+                _ => {
+                    return Err(MalError::InvalidFn(
+                        "overload should have params and a body",
+                    ))
+                }
+            }
+        }
+    } else {
+        match args {
+            [params, body] => arities.push(fn_arity(params, body)?),
+            _ => {
+                return Err(MalError::WrongArity {
+                    name: String::from("fn*"),
+                    expected: String::from("2"),
+                    actual: args.len(),
+                })
+            }
+        }
+    }
+    MalClojure::try_new(arities, env.clone())
+}
+
+fn fn_arity(params: &Rc<dyn MalType>, body: &Rc<dyn MalType>) -> Result<Arity, MalError> {
+    let params = params
+        .as_array()
+        .map_err(|err| err.for_arg("fn*", 1, params))?;
+    Arity::try_new(params, body.clone())
 }
 
 #[builtin_func(special)]
@@ -257,6 +636,15 @@ pub fn unquote(ast: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
     eval(ast.clone(), env)
 }
 
+/// Error for an `unquote` or `splice-unquote` form missing the form to evaluate.
+fn missing_unquoted(name: &str) -> MalError {
+    MalError::WrongArity {
+        name: String::from(name),
+        expected: String::from("1"),
+        actual: 0,
+    }
+}
+
 #[builtin_func(special)]
 pub fn quasiquote(to_quote: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
     let elems = if let Ok(list) = to_quote.as_type::<MalList>() {
@@ -265,7 +653,7 @@ pub fn quasiquote(to_quote: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
         } else if list.is_special("unquote") {
             return match list.get(1) {
                 Some(ast) => unquote(ast, env),
-                None => Err(MalError::TypeError),
+                None => Err(missing_unquoted("unquote")),
             };
         } else {
             list.values()
@@ -283,20 +671,20 @@ pub fn quasiquote(to_quote: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
                 if arr[0].is_special("unquote") {
                     let result = match arr.get(1) {
                         Some(ast) => eval(ast.clone(), env)?,
-                        None => return Err(MalError::TypeError),
+                        None => return Err(missing_unquoted("unquote")),
                     };
                     qq.push(result);
                 } else if arr[0].is_special("splice-unquote") {
                     let result = match arr.get(1) {
                         Some(ast) => eval(ast.clone(), env)?,
-                        None => return Err(MalError::TypeError),
+                        None => return Err(missing_unquoted("splice-unquote")),
                     };
-                    let result = result.as_array()?;
-                    for item in result {
-                        qq.push(item.clone());
+                    for item in seq::iter(&result) {
+                        qq.push(item?);
                     }
                 } else {
-                    qq.push(elem.clone());
+                    // Forms nested in lists and vectors are quasiquoted as well
+                    qq.push(quasiquote(elem, env)?);
                 }
             }
             _ => qq.push(elem.clone()),
@@ -343,7 +731,7 @@ pub fn macro_expand(mut ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
         let symbol = call[0].as_type()?;
         let lookup = env.get(symbol).unwrap();
         let macro_clojure = lookup.as_type::<MalClojure>().unwrap();
-        let (new_ast, new_env) = macro_clojure.call(&call.values()[1..], env)?;
+        let (new_ast, new_env) = macro_clojure.call(&call.rest())?;
         ast = eval(new_ast, &new_env)?;
     }
     Ok(ast)
@@ -353,20 +741,32 @@ pub fn macro_expand(mut ast: Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
 pub fn try_fn(ast: &Rc<dyn MalType>, catch: Option<&Rc<dyn MalType>>, env: &Rc<Env>) -> MalResult {
     match eval(ast.clone(), env) {
         Ok(result) => Ok(result),
+        // Misplaced `recur` is a mistake in the program rather than an exception to handle
+        Err(err) if matches!(err.inner(), MalError::RecurNotInTail) => Err(err),
         Err(err) => {
-            let exception = if let MalError::Exception(exception) = err {
-                exception
+            let stack_trace: Rc<dyn MalType> = match err.stack_trace() {
+                Some(stack_trace) => Rc::from(MalString::from(stack_trace.to_string())),
+                None => MalNil::new(),
+            };
+            let exception = if let MalError::Exception(exception) = err.inner() {
+                exception.clone()
             } else {
                 Rc::from(MalException::from(err))
             };
-            if let Some(catch) = catch {
-                let catch = catch.as_type::<MalList>()?;
-                if !catch.is_special("catch*") || catch.len() != 3 {
-                    return Err(MalError::TypeError);
-                }
-                let symbol = catch[1].as_type()?;
+            if let Some(catch_form) = catch {
+                let catch = match catch_form.as_type::<MalList>() {
+                    Ok(catch) if catch.is_special("catch*") && catch.len() == 3 => catch,
+                    _ => {
+                        let err = MalError::WrongType("`(catch* symbol body)` form");
+                        return Err(err.for_arg("try*", 2, catch_form));
+                    }
+                };
+                let symbol = catch[1]
+                    .as_type()
+                    .map_err(|err| err.for_arg("catch*", 1, &catch[1]))?;
                 let outer = Env::with_outer(env.clone());
                 outer.set(symbol, exception);
+                outer.set(&MalSymbol::from("*stack-trace*"), stack_trace);
                 eval(catch[2].clone(), &outer)
             } else {
                 Ok(exception)
@@ -0,0 +1,196 @@
+//! Namespaces, each with its own environment on top of the root environment with builtins.
+//!
+//! Namespace `my.app-util` is loaded from `my/app_util.mal` found in a directory of
+//! `*load-path*`, a namespace is only loaded the first time it's required.
+
+use std::{fs, path::PathBuf, rc::Rc};
+
+use mal_derive::builtin_func;
+
+use crate::{
+    env::{self, Env},
+    eval_source,
+    reader::Source,
+    types::{func::MalFuncPtr, MalKeyword, MalList, MalNil, MalString, MalSymbol, MalType, MalVec},
+    MalError, MalResult,
+};
+
+/// Evaluate the source in environment of a namespace, an `ns` form in the source only switches
+/// the namespace for the rest of the source.
+pub fn load_source(source: &Rc<Source>, env: &Rc<Env>) -> MalResult {
+    let name = env.namespace_name().unwrap_or_default().to_string();
+    let previous = env.set_current_namespace(&name);
+    let result = eval_source(source, env);
+    env.set_current_namespace(&previous);
+    result
+}
+
+/// Path of the file namespace `name` is loaded from, relative to a directory of the load path.
+fn namespace_path(name: &str) -> PathBuf {
+    let mut path: PathBuf = name.replace('-', "_").split('.').collect();
+    path.set_extension("mal");
+    path
+}
+
+fn find_namespace(name: &str, env: &Rc<Env>) -> Result<PathBuf, MalError> {
+    let load_path = env.get(&MalSymbol::from("*load-path*"))?;
+    let relative = namespace_path(name);
+    for dir in load_path.as_array()? {
+        let dir = dir.as_type::<MalString>()?;
+        let path = PathBuf::from(dir.as_str()).join(&relative);
+        if path.is_file() {
+            return Ok(path);
+        }
+    }
+    Err(MalError::NamespaceNotFound(name.to_string()))
+}
+
+/// Environment of namespace `name`, loading it the first time it's required.
+fn load(name: &str, env: &Rc<Env>) -> Result<Rc<Env>, MalError> {
+    let root = env::global(env);
+    if let Some(registry) = root.registry() {
+        if registry.is_loading(name) {
+            return Err(MalError::CircularRequire(name.to_string()));
+        }
+        if let Some(namespace) = registry.get(name) {
+            return Ok(namespace.clone());
+        }
+    }
+    let path = find_namespace(name, env)?;
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => return Err(MalError::IOError),
+    };
+    let source = Rc::from(Source::new(path.to_string_lossy(), text));
+    let namespace = root.create_namespace(name);
+    root.set_loading(name, true);
+    let result = load_source(&source, &namespace);
+    root.set_loading(name, false);
+    match result {
+        Ok(_) => Ok(namespace),
+        Err(err) => {
+            // Namespaces that failed to load are loaded again when required next time
+            root.remove_namespace(name);
+            Err(err)
+        }
+    }
+}
+
+/// Copy definitions of `namespace` to the namespace enclosing `env`, every definition is referred
+/// when `names` is `None`.
+fn refer_names(
+    namespace: &Env,
+    names: Option<&Rc<dyn MalType>>,
+    env: &Rc<Env>,
+) -> Result<(), MalError> {
+    let target = env::namespace_of(env);
+    let definitions = namespace.definitions();
+    let names = match names {
+        Some(names) => names.as_array()?,
+        None => {
+            for (symbol, value) in definitions {
+                target.set(&symbol, value);
+            }
+            return Ok(());
+        }
+    };
+    for name in names {
+        let symbol = name.as_type::<MalSymbol>()?;
+        match definitions.iter().find(|(defined, _)| defined == symbol) {
+            Some((_, value)) => target.set(symbol, value.clone()),
+            None => return Err(MalError::NotFound(name.clone())),
+        }
+    }
+    Ok(())
+}
+
+fn is_keyword(value: &Rc<dyn MalType>, name: &str) -> bool {
+    match value.as_type::<MalKeyword>() {
+        Ok(keyword) => keyword.value == name,
+        Err(_) => false,
+    }
+}
+
+/// Require namespace given as `my.ns` or `[my.ns :as alias :refer [names]]`, `:refer :all`
+/// refers every definition.
+fn require_spec(spec: &Rc<dyn MalType>, env: &Rc<Env>) -> Result<(), MalError> {
+    let items = if spec.is::<MalSymbol>() {
+        im_rc::vector![spec.clone()]
+    } else if spec.is::<MalVec>() {
+        spec.as_array()?.clone()
+    } else {
+        return Err(MalError::InvalidRequire(spec.clone()));
+    };
+    let name = match items.front().map(|name| name.as_type::<MalSymbol>()) {
+        Some(Ok(name)) => name,
+        _ => return Err(MalError::InvalidRequire(spec.clone())),
+    };
+    let namespace = load(name.as_str(), env)?;
+    let options: Vec<_> = items.iter().skip(1).cloned().collect();
+    for option in options.chunks(2) {
+        match option {
+            [key, alias] if is_keyword(key, ":as") => {
+                let alias = alias.as_type::<MalSymbol>()?;
+                env.add_alias(alias.as_str(), name.as_str());
+            }
+            [key, names] if is_keyword(key, ":refer") && is_keyword(names, ":all") => {
+                refer_names(&namespace, None, env)?;
+            }
+            [key, names] if is_keyword(key, ":refer") => {
+                refer_names(&namespace, Some(names), env)?;
+            }
+            _ => return Err(MalError::InvalidRequire(spec.clone())),
+        }
+    }
+    Ok(())
+}
+
+/// `(ns my.ns (:require [other.ns :as other]))` switches to namespace `my.ns`, creating it if
+/// needed, and requires namespaces of the `:require` clauses into it.
+#[builtin_func(special)]
+pub fn ns(name: &MalSymbol, clauses: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    let namespace = env::global(env).create_namespace(name.as_str());
+    namespace.set_current_namespace(name.as_str());
+    for clause in clauses {
+        let clause = match clause.as_type::<MalList>() {
+            Ok(clause) if matches!(clause.get(0), Some(key) if is_keyword(key, ":require")) => {
+                clause
+            }
+            _ => return Err(MalError::InvalidRequire(clause.clone())),
+        };
+        for spec in clause.iter().skip(1) {
+            require_spec(spec, &namespace)?;
+        }
+    }
+    Ok(MalNil::new())
+}
+
+#[builtin_func]
+pub fn require(specs: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    for spec in specs {
+        require_spec(spec, env)?;
+    }
+    Ok(MalNil::new())
+}
+
+/// `(refer 'my.ns)` refers every definition of a loaded namespace, `(refer 'my.ns :only '[a b])`
+/// only the given ones.
+#[builtin_func]
+pub fn refer(name: &MalSymbol, options: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    let namespace = env::global(env)
+        .registry()
+        .and_then(|registry| registry.get(&env.resolve_alias(name.as_str())).cloned());
+    let namespace = match namespace {
+        Some(namespace) => namespace,
+        None => return Err(MalError::NamespaceNotFound(name.to_string())),
+    };
+    match options {
+        [] => refer_names(&namespace, None, env)?,
+        [key, names] if is_keyword(key, ":only") => refer_names(&namespace, Some(names), env)?,
+        _ => {
+            let options: MalList = options.iter().collect();
+            return Err(MalError::InvalidRequire(Rc::from(options)));
+        }
+    }
+    Ok(MalNil::new())
+}
@@ -0,0 +1,74 @@
+use std::rc::Rc;
+
+use mal_derive::builtin_func;
+
+use crate::{
+    env::Env,
+    types::{func::MalFuncPtr, MalList, MalMatcher, MalNil, MalRegex, MalString, MalType},
+    MalError, MalResult,
+};
+
+/// Compiles a pattern through the cache, reporting invalid patterns as MAL errors.
+pub fn compile(pattern: &str) -> Result<Rc<MalRegex>, MalError> {
+    MalRegex::compile(pattern).map_err(|err| MalError::InvalidRegex(err.to_string()))
+}
+
+#[builtin_func(symbol = "re-pattern")]
+pub fn re_pattern(pattern: &Rc<dyn MalType>) -> MalResult {
+    if pattern.is::<MalRegex>() {
+        Ok(pattern.clone())
+    } else if let Ok(string) = pattern.as_type::<MalString>() {
+        Ok(compile(string.as_str())?)
+    } else {
+        Err(MalError::WrongType("string or regex").for_arg("re-pattern", 1, pattern))
+    }
+}
+
+#[builtin_func(symbol = "re-matcher")]
+pub fn re_matcher(regex: &MalRegex, string: &MalString) -> MalResult {
+    let matcher = MalMatcher::new(regex.clone(), string.value.clone());
+    Ok(Rc::from(matcher))
+}
+
+#[builtin_func(symbol = "re-find")]
+pub fn re_find(first: &Rc<dyn MalType>, string: Option<&Rc<dyn MalType>>) -> MalResult {
+    let found = match string {
+        Some(string) => {
+            let regex = first
+                .as_type::<MalRegex>()
+                .map_err(|err| err.for_arg("re-find", 1, first))?;
+            let string = string
+                .as_type::<MalString>()
+                .map_err(|err| err.for_arg("re-find", 2, string))?;
+            regex.find_at(string.as_str(), 0).map(|(value, _)| value)
+        }
+        None => first
+            .as_type::<MalMatcher>()
+            .map_err(|err| err.for_arg("re-find", 1, first))?
+            .find(),
+    };
+    Ok(found.unwrap_or_else(|| MalNil::new()))
+}
+
+#[builtin_func(symbol = "re-matches")]
+pub fn re_matches(regex: &MalRegex, string: &MalString) -> MalResult {
+    let found = regex
+        .matches(string.as_str())
+        .map_err(|err| MalError::InvalidRegex(err.to_string()))?;
+    Ok(found.unwrap_or_else(|| MalNil::new()))
+}
+
+#[builtin_func(symbol = "re-seq")]
+pub fn re_seq(regex: &MalRegex, string: &MalString) -> MalResult {
+    let list: MalList = regex
+        .find_all(string.as_str())
+        .into_iter()
+        .map(|(value, _)| value)
+        .collect();
+    Ok(Rc::from(list))
+}
+
+#[builtin_func(symbol = "re-groups")]
+pub fn re_groups(matcher: &MalMatcher) -> MalResult {
+    matcher.groups().ok_or(MalError::NoMatch)
+}
@@ -1,38 +1,67 @@
 use crate::types::{
-    MalBool, MalHashMap, MalInt, MalKeyword, MalList, MalNil, MalString, MalSymbol, MalType, MalVec,
+    MalBigInt, MalBool, MalChar, MalFloat, MalHashMap, MalInt, MalKeyword, MalList, MalNil,
+    MalRegex, MalSet, MalString, MalSymbol, MalType, MalVec,
 };
 use lazy_static::lazy_static;
+use num_bigint::BigInt;
 use regex::Regex;
 use std::{
     convert::{TryFrom, TryInto},
-    iter::Peekable,
     rc::Rc,
     str::FromStr,
 };
 use thiserror::Error;
 
+pub mod source;
 pub mod token;
 pub mod tokenizer;
 
+pub use source::{Location, Source, Span};
 pub use token::{FullToken, Token};
 pub use tokenizer::Tokenizer;
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum ParseError {
     #[error("Expected matching '\"'.")]
-    UnbalancedEmptyString,
+    UnbalancedEmptyString(Span),
     #[error("Expected matching '\"' for `\"{0}`.")]
-    UnbalancedString(String),
-    #[error("Expected matching '['.")]
-    UnbalancedList,
+    UnbalancedString(String, Span),
+    #[error("Unknown character literal `\\{0}`.")]
+    InvalidChar(String, Span),
+    #[error("Expected matching ')'.")]
+    UnbalancedList(Span),
     #[error("Expected matching ']'.")]
-    UnbalancedVec,
+    UnbalancedVec(Span),
     #[error("Expected matching '}}'.")]
-    UnbalancedMap,
+    UnbalancedMap(Span),
+    #[error("Map literal should contain even number of forms.")]
+    OddMapLiteral(Span),
+    #[error("Invalid regular expression: {0}")]
+    InvalidRegex(String, Span),
+    #[error("Anonymous function literals can't be nested.")]
+    NestedFnLiteral(Span),
     #[error("Unexpected token {0}.")]
-    UnexpectedToken(Token),
+    UnexpectedToken(Token, Span),
     #[error("Reached end of input")]
-    EOF,
+    EOF(Span),
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnbalancedEmptyString(span)
+            | ParseError::UnbalancedString(_, span)
+            | ParseError::InvalidChar(_, span)
+            | ParseError::UnbalancedList(span)
+            | ParseError::UnbalancedVec(span)
+            | ParseError::UnbalancedMap(span)
+            | ParseError::OddMapLiteral(span)
+            | ParseError::InvalidRegex(_, span)
+            | ParseError::NestedFnLiteral(span)
+            | ParseError::UnexpectedToken(_, span)
+            | ParseError::EOF(span) => *span,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -42,16 +71,21 @@ pub enum AtomKind {
     Symbol,
     Nil,
     Int,
+    Float,
     Builtin,
 }
 
 impl From<&str> for AtomKind {
     fn from(atom: &str) -> Self {
         lazy_static! {
-            static ref INT_RE: Regex = Regex::new("^-?\\d+$").unwrap();
+            static ref INT_RE: Regex = Regex::new("^-?\\d+N?$").unwrap();
+            static ref FLOAT_RE: Regex =
+                Regex::new("^-?(\\d+\\.\\d*|\\.\\d+|\\d+)([eE][+-]?\\d+)?$").unwrap();
         }
         if INT_RE.is_match_at(atom, 0) {
             AtomKind::Int
+        } else if FLOAT_RE.is_match_at(atom, 0) {
+            AtomKind::Float
         } else if atom.starts_with(':') {
             AtomKind::Keyword
         } else if atom == "true" || atom == "false" {
@@ -60,7 +94,9 @@ impl From<&str> for AtomKind {
             AtomKind::Nil
         } else {
             match atom {
-                "def!" | "let*" | "do" | "if" | "fn*" => AtomKind::Builtin,
+                "def!" | "let*" | "do" | "if" | "fn*" | "loop*" | "recur" | "ns" | "lazy-seq" => {
+                    AtomKind::Builtin
+                }
                 _ => AtomKind::Symbol,
             }
         }
@@ -70,28 +106,69 @@ impl From<&str> for AtomKind {
 #[derive(Debug)]
 pub struct Reader<'a> {
     tokenizer: Tokenizer<'a>,
+    source: Rc<Source>,
+    peeked: Option<Option<Result<FullToken, ParseError>>>,
+    position: usize,
+    in_fn_literal: bool,
 }
 
 impl<'a> From<&'a str> for Reader<'a> {
     fn from(input: &'a str) -> Self {
         let tokenizer = Tokenizer::from(input);
-        Self { tokenizer }
+        let source = Rc::from(Source::from(input));
+        Self {
+            tokenizer,
+            source,
+            peeked: None,
+            position: 0,
+            in_fn_literal: false,
+        }
+    }
+}
+
+impl<'a> From<&'a Rc<Source>> for Reader<'a> {
+    fn from(source: &'a Rc<Source>) -> Self {
+        let tokenizer = Tokenizer::from(source.text());
+        Self {
+            tokenizer,
+            source: source.clone(),
+            peeked: None,
+            position: 0,
+            in_fn_literal: false,
+        }
     }
 }
 
 impl Iterator for Reader<'_> {
-    type Item = Result<Token, ParseError>;
+    type Item = Result<FullToken, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.tokenizer.next() {
-            Some(Ok(full_token)) => {
-                let token = full_token.into();
-                match token {
+        let next = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.next_token(),
+        };
+        if let Some(Ok(full_token)) = &next {
+            self.position = full_token.stop;
+        }
+        next
+    }
+}
+
+pub type ReaderResult = Result<Rc<dyn MalType>, ParseError>;
+
+impl Reader<'_> {
+    fn next_token(&mut self) -> Option<Result<FullToken, ParseError>> {
+        loop {
+            match self.tokenizer.next() {
+                Some(Ok(full_token)) => match full_token.as_token() {
                     Token::TildeAt
                     | Token::LeftSquare
                     | Token::RightSquare
                     | Token::LeftCurly
                     | Token::RightCurly
+                    | Token::HashCurly
+                    | Token::HashParen
+                    | Token::HashUnderscore
                     | Token::LeftParen
                     | Token::RightParen
                     | Token::Apostrophe
@@ -100,132 +177,274 @@ impl Iterator for Reader<'_> {
                     | Token::Caret
                     | Token::At
                     | Token::String(_)
-                    | Token::Atom(_) => Some(Ok(token)),
+                    | Token::Regex(_)
+                    | Token::Char(_)
+                    | Token::Atom(_) => return Some(Ok(full_token)),
                     Token::Comment(_)
                     | Token::Space
                     | Token::Newline
                     | Token::CarriageReturn
                     | Token::Tab
-                    | Token::Comma => self.next(),
-                }
+                    | Token::Comma => continue,
+                },
+                Some(Err(err)) => return Some(Err(err)),
+                None => return None,
             }
-            Some(Err(err)) => Some(Err(err)),
-            None => None,
         }
     }
-}
 
-pub type ReaderResult = Result<Rc<dyn MalType>, ParseError>;
+    pub fn peek(&mut self) -> Option<&Result<FullToken, ParseError>> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next_token());
+        }
+        self.peeked.as_ref().unwrap().as_ref()
+    }
 
-impl Reader<'_> {
-    pub fn read_from(reader: &mut Peekable<Self>) -> ReaderResult {
-        let token = match reader.peek() {
-            Some(Ok(token)) => token,
-            Some(Err(_)) => return Err(reader.next().unwrap().unwrap_err()),
-            None => return Err(ParseError::EOF),
+    pub fn source(&self) -> &Rc<Source> {
+        &self.source
+    }
+
+    /// Location from `start` to the end of the last consumed token.
+    fn location(&self, start: usize) -> Location {
+        Location::new(self.source.clone(), Span::new(start, self.position))
+    }
+
+    fn eof(&self) -> ParseError {
+        let end = self.source.text().chars().count();
+        ParseError::EOF(Span::new(end, end))
+    }
+
+    /// Skips forms discarded with `#_`.
+    pub fn skip_discarded(&mut self) -> Result<(), ParseError> {
+        while let Some(Ok(full_token)) = self.peek() {
+            if *full_token != Token::HashUnderscore {
+                break;
+            }
+            self.next();
+            self.read_form()?;
+        }
+        Ok(())
+    }
+
+    pub fn read_form(&mut self) -> ReaderResult {
+        self.skip_discarded()?;
+        let full_token = match self.peek() {
+            Some(Ok(full_token)) => full_token,
+            Some(Err(_)) => return Err(self.next().unwrap().unwrap_err()),
+            None => return Err(self.eof()),
         };
 
-        match token {
-            Token::LeftParen => Reader::read_list(reader),
-            Token::LeftSquare => Reader::read_vec(reader),
-            Token::LeftCurly => Reader::read_hashmap(reader),
+        let span = full_token.span();
+        match full_token.as_token() {
+            Token::LeftParen => self.read_list(),
+            Token::LeftSquare => self.read_vec(),
+            Token::LeftCurly => self.read_hashmap(),
+            Token::HashCurly => self.read_set(),
+            Token::HashParen => self.read_fn_literal(),
+            Token::Regex(_) => self.read_regex(),
             Token::Apostrophe | Token::Tilde | Token::BackTick | Token::TildeAt => {
-                Reader::read_quote(reader)
+                self.read_quote()
             }
-            Token::At => Reader::read_deref(reader),
-            Token::RightParen => Err(ParseError::UnbalancedList),
-            Token::RightSquare => Err(ParseError::UnbalancedVec),
-            Token::RightCurly => Err(ParseError::UnbalancedMap),
-            Token::String(_) | Token::Atom(_) => Reader::read_atom(reader),
-            Token::Caret => unimplemented!(),
+            Token::At => self.read_deref(),
+            Token::Caret => self.read_meta(),
+            Token::RightParen => Err(ParseError::UnbalancedList(span)),
+            Token::RightSquare => Err(ParseError::UnbalancedVec(span)),
+            Token::RightCurly => Err(ParseError::UnbalancedMap(span)),
+            Token::String(_) | Token::Char(_) | Token::Atom(_) => self.read_atom(),
             Token::Comment(_)
             | Token::Space
             | Token::Newline
             | Token::CarriageReturn
             | Token::Tab
-            | Token::Comma => unreachable!(),
+            | Token::Comma
+            | Token::HashUnderscore => unreachable!(),
         }
     }
 
-    fn read_quote(reader: &mut Peekable<Self>) -> ReaderResult {
-        let token = reader.next().unwrap()?;
+    fn read_quote(&mut self) -> ReaderResult {
+        let full_token = self.next().unwrap()?;
+        let start = full_token.start;
+        let token: Token = full_token.into();
         let symbol = match token {
             Token::TildeAt | Token::Apostrophe | Token::BackTick | Token::Tilde => {
                 token.try_into().unwrap()
             }
             _ => panic!("Invalid token: {:?}", token),
         };
-        let quoted = Reader::read_from(reader)?;
-        Ok(Rc::from(MalList::from(vec![symbol, quoted])))
+        let quoted = self.read_form()?;
+        let list = MalList::from(vec![symbol, quoted]).with_location(self.location(start));
+        Ok(Rc::from(list))
     }
 
-    fn read_deref(reader: &mut Peekable<Self>) -> ReaderResult {
-        assert_eq!(reader.next().unwrap().unwrap(), Token::At);
+    fn read_deref(&mut self) -> ReaderResult {
+        let full_token = self.next().unwrap().unwrap();
+        assert_eq!(full_token, Token::At);
         let symbol: Rc<dyn MalType> = Rc::from(MalSymbol::from("deref".to_string()));
-        let derefed = Reader::read_from(reader)?;
-        Ok(Rc::from(MalList::from(vec![symbol, derefed])))
+        let derefed = self.read_form()?;
+        let list =
+            MalList::from(vec![symbol, derefed]).with_location(self.location(full_token.start));
+        Ok(Rc::from(list))
     }
 
-    fn read_list(reader: &mut Peekable<Self>) -> ReaderResult {
-        let list = Reader::read_between(reader, Token::LeftParen, Token::RightParen)?;
-        Ok(Rc::from(MalList::from(list)))
+    fn read_meta(&mut self) -> ReaderResult {
+        let full_token = self.next().unwrap().unwrap();
+        assert_eq!(full_token, Token::Caret);
+        let symbol: Rc<dyn MalType> = Rc::from(MalSymbol::from("with-meta"));
+        let meta = self.read_form()?;
+        let form = self.read_form()?;
+        let list =
+            MalList::from(vec![symbol, form, meta]).with_location(self.location(full_token.start));
+        Ok(Rc::from(list))
     }
 
-    fn read_vec(reader: &mut Peekable<Self>) -> ReaderResult {
-        let list = Reader::read_between(reader, Token::LeftSquare, Token::RightSquare)?;
+    fn read_list(&mut self) -> ReaderResult {
+        let (list, span) = self.read_between(Token::LeftParen, Token::RightParen)?;
+        let list = MalList::from(list).with_location(self.location(span.start));
+        Ok(Rc::from(list))
+    }
+
+    fn read_vec(&mut self) -> ReaderResult {
+        let (list, _) = self.read_between(Token::LeftSquare, Token::RightSquare)?;
         Ok(Rc::from(MalVec::from(list)))
     }
 
-    fn read_hashmap(reader: &mut Peekable<Self>) -> ReaderResult {
-        let list = Reader::read_between(reader, Token::LeftCurly, Token::RightCurly)?;
+    fn read_hashmap(&mut self) -> ReaderResult {
+        let (list, span) = self.read_between(Token::LeftCurly, Token::RightCurly)?;
         match MalHashMap::try_from(list) {
             Ok(map) => Ok(Rc::from(map)),
-            Err(_) => todo!(),
+            Err(_) => Err(ParseError::OddMapLiteral(span)),
+        }
+    }
+
+    fn read_set(&mut self) -> ReaderResult {
+        let (list, _) = self.read_between(Token::HashCurly, Token::RightCurly)?;
+        let set: MalSet = list.into_iter().collect();
+        Ok(Rc::from(set))
+    }
+
+    fn read_fn_literal(&mut self) -> ReaderResult {
+        let span = self.peek().unwrap().as_ref().unwrap().span();
+        if self.in_fn_literal {
+            return Err(ParseError::NestedFnLiteral(span));
+        }
+        self.in_fn_literal = true;
+        let body = self.read_between(Token::HashParen, Token::RightParen);
+        self.in_fn_literal = false;
+        let (body, span) = body?;
+
+        let mut arity = 0;
+        let mut variadic = false;
+        let body: Vec<_> = body
+            .iter()
+            .map(|form| fn_literal_args(form, &mut arity, &mut variadic))
+            .collect();
+        let mut params: Vec<Rc<dyn MalType>> = (1..=arity)
+            .map(|index| Rc::from(MalSymbol::from(format!("%{}", index))) as Rc<dyn MalType>)
+            .collect();
+        if variadic {
+            params.push(Rc::from(MalSymbol::from("&")));
+            params.push(Rc::from(MalSymbol::from("%&")));
+        }
+
+        let location = self.location(span.start);
+        let symbol: Rc<dyn MalType> = Rc::from(MalSymbol::from("fn*"));
+        let body = MalList::from(body).with_location(location.clone());
+        let list = MalList::from(vec![
+            symbol,
+            Rc::from(MalList::from(params)),
+            Rc::from(body),
+        ])
+        .with_location(location);
+        Ok(Rc::from(list))
+    }
+
+    fn read_regex(&mut self) -> ReaderResult {
+        let full_token = self.next().unwrap().unwrap();
+        let span = full_token.span();
+        let pattern = match full_token.into() {
+            Token::Regex(pattern) => pattern,
+            _ => unreachable!(),
+        };
+        match MalRegex::compile(&pattern) {
+            Ok(regex) => Ok(regex),
+            Err(err) => Err(ParseError::InvalidRegex(err.to_string(), span)),
         }
     }
 
     fn read_between(
-        reader: &mut Peekable<Self>,
+        &mut self,
         start: Token,
         stop: Token,
-    ) -> Result<Vec<Rc<dyn MalType>>, ParseError> {
-        assert!(match (&start, &stop) {
-            (Token::LeftParen, Token::RightParen) => true,
-            (Token::LeftSquare, Token::RightSquare) => true,
-            (Token::LeftCurly, Token::RightCurly) => true,
-            _ => false,
-        });
-        assert_eq!(reader.next().unwrap().unwrap(), start);
+    ) -> Result<(Vec<Rc<dyn MalType>>, Span), ParseError> {
+        assert!(matches!(
+            (&start, &stop),
+            (Token::LeftParen, Token::RightParen)
+                | (Token::LeftSquare, Token::RightSquare)
+                | (Token::LeftCurly, Token::RightCurly)
+                | (Token::HashCurly, Token::RightCurly)
+                | (Token::HashParen, Token::RightParen)
+        ));
+        let opening = self.next().unwrap().unwrap();
+        assert_eq!(opening, start);
 
         let mut list = Vec::new();
         loop {
-            match reader.peek() {
+            self.skip_discarded()?;
+            match self.peek() {
                 Some(Ok(token)) if *token == stop => break,
                 Some(Ok(_)) => {
-                    list.push(Reader::read_from(reader)?);
+                    list.push(self.read_form()?);
                 }
-                Some(Err(_)) => return Err(reader.next().unwrap().unwrap_err()),
+                Some(Err(_)) => return Err(self.next().unwrap().unwrap_err()),
                 None => match start {
-                    Token::LeftParen => return Err(ParseError::UnbalancedList),
-                    Token::LeftSquare => return Err(ParseError::UnbalancedVec),
-                    Token::LeftCurly => return Err(ParseError::UnbalancedMap),
+                    Token::LeftParen | Token::HashParen => {
+                        return Err(ParseError::UnbalancedList(opening.span()))
+                    }
+                    Token::LeftSquare => return Err(ParseError::UnbalancedVec(opening.span())),
+                    Token::LeftCurly | Token::HashCurly => {
+                        return Err(ParseError::UnbalancedMap(opening.span()))
+                    }
                     _ => unreachable!(),
                 },
             }
         }
-        assert_eq!(reader.next().unwrap().unwrap(), stop);
-        Ok(list)
+        let closing = self.next().unwrap().unwrap();
+        assert_eq!(closing, stop);
+        Ok((list, Span::new(opening.start, closing.stop)))
     }
 
-    fn read_atom(reader: &mut Peekable<Self>) -> ReaderResult {
+    fn read_atom(&mut self) -> ReaderResult {
         lazy_static! {
-            static ref INT_RE: Regex = Regex::new("^-?\\d+$").unwrap();
+            static ref INT_RE: Regex = Regex::new("^-?\\d+N?$").unwrap();
+            static ref FLOAT_RE: Regex =
+                Regex::new("^-?(\\d+\\.\\d*|\\.\\d+|\\d+)([eE][+-]?\\d+)?$").unwrap();
         }
-        match reader.next() {
-            Some(Ok(Token::Atom(atom))) => {
+        let full_token = match self.next() {
+            Some(Ok(full_token)) => full_token,
+            Some(Err(err)) => return Err(err),
+            None => return Err(self.eof()),
+        };
+        let span = full_token.span();
+        match full_token.into() {
+            Token::Atom(atom) => {
                 if INT_RE.is_match_at(&atom, 0) {
-                    let value = i64::from_str(&atom).unwrap();
-                    Ok(Rc::from(MalInt::from(value)))
+                    // Literals with `N` suffix or that don't fit into `i64` are read as big integers
+                    match atom.strip_suffix('N') {
+                        Some(digits) => {
+                            let value = BigInt::from_str(digits).unwrap();
+                            Ok(Rc::from(MalBigInt::from(value)))
+                        }
+                        None => match i64::from_str(&atom) {
+                            Ok(value) => Ok(Rc::from(MalInt::from(value))),
+                            Err(_) => {
+                                let value = BigInt::from_str(&atom).unwrap();
+                                Ok(Rc::from(MalBigInt::from(value)))
+                            }
+                        },
+                    }
+                } else if FLOAT_RE.is_match_at(&atom, 0) {
+                    let value = f64::from_str(&atom).unwrap();
+                    Ok(Rc::from(MalFloat::from(value)))
                 } else if atom.starts_with(':') {
                     Ok(Rc::from(MalKeyword::from(atom)))
                 } else if atom == "true" {
@@ -238,17 +457,71 @@ impl Reader<'_> {
                     Ok(Rc::from(MalSymbol::from(atom)))
                 }
             }
-            Some(Ok(Token::String(string))) => Ok(Rc::from(MalString::from(string))),
-            Some(Ok(token)) => Err(ParseError::UnexpectedToken(token)),
-            Some(Err(err)) => Err(err),
-            None => panic!("Reached end of input."),
+            Token::String(string) => Ok(Rc::from(MalString::from(string))),
+            Token::Char(ch) => Ok(Rc::from(MalChar::from(ch))),
+            token => Err(ParseError::UnexpectedToken(token, span)),
+        }
+    }
+}
+
+/// Replaces `%` with `%1` in the body of `#(...)`, tracking the highest numbered argument and
+/// whether `%&` is used.
+fn fn_literal_args(
+    form: &Rc<dyn MalType>,
+    arity: &mut usize,
+    variadic: &mut bool,
+) -> Rc<dyn MalType> {
+    if let Ok(symbol) = form.as_type::<MalSymbol>() {
+        let name = symbol.as_str();
+        if name == "%" {
+            *arity = (*arity).max(1);
+            return Rc::from(MalSymbol::from("%1"));
+        } else if name == "%&" {
+            *variadic = true;
+        } else if let Some(index) = name.strip_prefix('%') {
+            if let Ok(index) = usize::from_str(index) {
+                *arity = (*arity).max(index);
+            }
         }
+        form.clone()
+    } else if let Ok(list) = form.as_type::<MalList>() {
+        let items = fn_literal_items(list.iter(), arity, variadic);
+        let mut result = MalList::from(items);
+        if let Some(location) = list.location() {
+            result = result.with_location(location.clone());
+        }
+        Rc::from(result)
+    } else if let Ok(vector) = form.as_type::<MalVec>() {
+        Rc::from(MalVec::from(fn_literal_items(
+            vector.iter(),
+            arity,
+            variadic,
+        )))
+    } else if let Ok(map) = form.as_type::<MalHashMap>() {
+        let entries = map.iter().flat_map(|(key, value)| [key, value]);
+        let items = fn_literal_items(entries, arity, variadic);
+        Rc::from(MalHashMap::try_from(items).unwrap())
+    } else if let Ok(set) = form.as_type::<MalSet>() {
+        let items = fn_literal_items(set.iter(), arity, variadic);
+        Rc::from(items.into_iter().collect::<MalSet>())
+    } else {
+        form.clone()
     }
 }
 
+fn fn_literal_items<'a>(
+    items: impl Iterator<Item = &'a Rc<dyn MalType>>,
+    arity: &mut usize,
+    variadic: &mut bool,
+) -> Vec<Rc<dyn MalType>> {
+    items
+        .map(|item| fn_literal_args(item, arity, variadic))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::reader::{ParseError, Reader, Token};
+    use crate::reader::{FullToken, ParseError, Reader, Span, Token};
 
     #[test]
     fn dont_read_whitespace_and_commas() {
@@ -316,10 +589,20 @@ mod tests {
     #[test]
     fn error_on_unbalanced_strings() {
         let mut reader = Reader::from("\"unbalanced\" \"strings");
-        assert_eq!(reader.next(), Some(Ok(Token::String("unbalanced".into()))));
         assert_eq!(
             reader.next(),
-            Some(Err(ParseError::UnbalancedString("strings".into())))
+            Some(Ok(FullToken::new(
+                Token::String("unbalanced".into()),
+                0,
+                12
+            )))
+        );
+        assert_eq!(
+            reader.next(),
+            Some(Err(ParseError::UnbalancedString(
+                "strings".into(),
+                Span::new(13, 21)
+            )))
         );
     }
 
@@ -0,0 +1,106 @@
+use std::{fmt::Display, rc::Rc};
+
+/// Range of characters `[start, stop)` in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub stop: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, stop: usize) -> Self {
+        Self { start, stop }
+    }
+}
+
+/// Named text that forms are read from, either a file or input entered to the REPL.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Source {
+    name: String,
+    text: String,
+}
+
+impl From<&str> for Source {
+    fn from(text: &str) -> Self {
+        Self::new("<input>", text)
+    }
+}
+
+impl Source {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            text: text.into(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    source: Rc<Source>,
+    span: Span,
+}
+
+impl Location {
+    pub fn new(source: Rc<Source>, span: Span) -> Self {
+        Self { source, span }
+    }
+
+    pub fn source(&self) -> &Rc<Source> {
+        &self.source
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// One based line and column of the start of the span.
+    pub fn line_column(&self) -> (usize, usize) {
+        let mut line = 1;
+        let mut column = 1;
+        for ch in self.source.text.chars().take(self.span.start) {
+            if ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        (line, column)
+    }
+
+    /// Line of the source containing the span with the span underlined by carets.
+    pub fn snippet(&self) -> String {
+        let (line, column) = self.line_column();
+        let text = self.source.text.lines().nth(line - 1).unwrap_or("");
+        let available = text.chars().count().saturating_sub(column - 1).max(1);
+        let width = (self.span.stop - self.span.start).clamp(1, available);
+
+        let number = line.to_string();
+        let gutter = " ".repeat(number.len());
+        format!(
+            "{} |\n{} | {}\n{} | {}{}",
+            gutter,
+            number,
+            text,
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (line, column) = self.line_column();
+        write!(f, "{}:{}:{}", self.source.name, line, column)
+    }
+}
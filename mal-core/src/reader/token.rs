@@ -1,6 +1,8 @@
 use std::{convert::TryInto, fmt::Display, rc::Rc};
 
-use crate::types::{MalSymbol, MalType};
+use crate::types::{MalChar, MalSymbol, MalType};
+
+use super::Span;
 
 impl PartialEq<Token> for FullToken {
     fn eq(&self, other: &Token) -> bool {
@@ -15,6 +17,9 @@ pub enum Token {
     RightSquare,
     LeftCurly,
     RightCurly,
+    HashCurly,
+    HashParen,
+    HashUnderscore,
     LeftParen,
     RightParen,
     Apostrophe,
@@ -28,6 +33,8 @@ pub enum Token {
     Tab,
     Comma,
     String(String),
+    Regex(String),
+    Char(char),
     Comment(String),
     Atom(String),
 }
@@ -47,9 +54,14 @@ impl TryInto<Rc<dyn MalType>> for Token {
             | Token::RightSquare
             | Token::LeftCurly
             | Token::RightCurly
+            | Token::HashCurly
+            | Token::HashParen
+            | Token::HashUnderscore
             | Token::LeftParen
             | Token::RightParen
             | Token::String(_)
+            | Token::Regex(_)
+            | Token::Char(_)
             | Token::Comment(_)
             | Token::Space
             | Token::Newline
@@ -82,6 +94,10 @@ impl FullToken {
     pub fn as_token(&self) -> &Token {
         &self.token
     }
+
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.stop)
+    }
 }
 
 impl Display for Token {
@@ -92,6 +108,9 @@ impl Display for Token {
             Token::RightSquare => write!(f, "]"),
             Token::LeftCurly => write!(f, "{{"),
             Token::RightCurly => write!(f, "}}"),
+            Token::HashCurly => write!(f, "#{{"),
+            Token::HashParen => write!(f, "#("),
+            Token::HashUnderscore => write!(f, "#_"),
             Token::LeftParen => write!(f, "("),
             Token::RightParen => write!(f, ")"),
             Token::Apostrophe => write!(f, "'"),
@@ -99,7 +118,9 @@ impl Display for Token {
             Token::Tilde => write!(f, "~"),
             Token::Caret => write!(f, "^"),
             Token::At => write!(f, "@"),
-            Token::String(string) => write!(f, "\"{}\"", string),
+            Token::String(string) => write!(f, "{:?}", string),
+            Token::Char(ch) => write!(f, "{:?}", MalChar::from(*ch)),
+            Token::Regex(pattern) => write!(f, "#\"{}\"", pattern),
             Token::Comment(comment) => write!(f, ";{}", comment),
             Token::Atom(atom) => write!(f, "{}", atom),
             Token::Space => write!(f, " "),
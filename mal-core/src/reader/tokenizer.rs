@@ -1,4 +1,6 @@
-use super::{token::is_special_char, FullToken, ParseError, Token};
+use crate::types::MalChar;
+
+use super::{token::is_special_char, FullToken, ParseError, Span, Token};
 
 #[derive(Debug)]
 pub struct Tokenizer<'a> {
@@ -72,6 +74,54 @@ impl Iterator for Tokenizer<'_> {
                 self.index += 1;
                 Token::RightCurly
             }
+            Some('#') if self.input.chars().nth(self.index + 1) == Some('{') => {
+                self.index += 2;
+                Token::HashCurly
+            }
+            Some('#') if self.input.chars().nth(self.index + 1) == Some('(') => {
+                self.index += 2;
+                Token::HashParen
+            }
+            Some('#') if self.input.chars().nth(self.index + 1) == Some('_') => {
+                self.index += 2;
+                Token::HashUnderscore
+            }
+            Some('#') if self.input.chars().nth(self.index + 1) == Some('"') => {
+                self.index += 2;
+
+                // Backslashes are kept in the pattern to be interpreted by the regex engine,
+                // except in front of '"' where they only keep the literal from ending
+                let mut remaining = self.input.chars().skip(self.index);
+                let mut pattern = String::new();
+                loop {
+                    let ch = match remaining.next() {
+                        Some(ch) => ch,
+                        None => {
+                            let span = Span::new(start, self.index);
+                            if pattern.is_empty() {
+                                return Some(Err(ParseError::UnbalancedEmptyString(span)));
+                            } else {
+                                return Some(Err(ParseError::UnbalancedString(pattern, span)));
+                            }
+                        }
+                    };
+                    self.index += 1;
+                    match ch {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(escaped) = remaining.next() {
+                                self.index += 1;
+                                if escaped != '"' {
+                                    pattern.push(ch);
+                                }
+                                pattern.push(escaped);
+                            }
+                        }
+                        _ => pattern.push(ch),
+                    }
+                }
+                Token::Regex(pattern)
+            }
             Some('(') => {
                 self.index += 1;
                 Token::LeftParen
@@ -99,30 +149,74 @@ impl Iterator for Tokenizer<'_> {
             Some('"') => {
                 self.index += 1;
 
-                let mut remaining = self.input.get(self.index..).unwrap().chars().peekable();
+                let mut remaining = self.input.chars().skip(self.index);
                 let mut string = String::new();
-                let string_token = loop {
-                    match remaining.next() {
-                        Some(ch) => {
-                            self.index += 1;
-                            match ch {
-                                '"' => break Token::String(string),
-                                _ => string.push(ch),
-                            }
-                        }
+
+                loop {
+                    let ch = match remaining.next() {
+                        Some(ch) => ch,
                         None => {
+                            let span = Span::new(start, self.index);
                             if string.is_empty() {
-                                return Some(Err(ParseError::UnbalancedEmptyString));
+                                return Some(Err(ParseError::UnbalancedEmptyString(span)));
                             } else {
-                                return Some(Err(ParseError::UnbalancedString(string)));
+                                return Some(Err(ParseError::UnbalancedString(string, span)));
                             }
                         }
                     };
-                };
-                string_token
+                    self.index += 1;
+                    match ch {
+                        '"' => break,
+                        '\\' => {
+                            // Escaped character is consumed with the backslash, trailing
+                            // backslash is reported as unbalanced string on the next iteration
+                            if let Some(escaped) = remaining.next() {
+                                self.index += 1;
+                                match escaped {
+                                    'n' => string.push('\n'),
+                                    't' => string.push('\t'),
+                                    'r' => string.push('\r'),
+                                    '"' | '\\' => string.push(escaped),
+                                    // Unknown escapes are kept as written
+                                    _ => {
+                                        string.push('\\');
+                                        string.push(escaped);
+                                    }
+                                }
+                            }
+                        }
+                        _ => string.push(ch),
+                    }
+                }
+                Token::String(string)
+            }
+            Some('\\') => {
+                self.index += 1;
+
+                // First character is taken even if it's special, `\(` is the character '('
+                let mut remaining = self.input.chars().skip(self.index);
+                let mut literal = String::new();
+                if let Some(ch) = remaining.next() {
+                    self.index += 1;
+                    literal.push(ch);
+                }
+                for ch in remaining {
+                    if is_special_char(ch) || ch == '\\' {
+                        break;
+                    }
+                    self.index += 1;
+                    literal.push(ch);
+                }
+                match MalChar::from_literal(&literal) {
+                    Some(ch) => Token::Char(ch),
+                    None => {
+                        let span = Span::new(start, self.index);
+                        return Some(Err(ParseError::InvalidChar(literal, span)));
+                    }
+                }
             }
             Some(';') => {
-                let chars = self.input.get(self.index..).unwrap().chars();
+                let chars = self.input.chars().skip(self.index);
                 let mut result = String::new();
                 for ch in chars {
                     if ch != '\n' {
@@ -135,7 +229,7 @@ impl Iterator for Tokenizer<'_> {
                 Token::Comment(result)
             }
             Some(_) => {
-                let chars = self.input.get(self.index..).unwrap().chars();
+                let chars = self.input.chars().skip(self.index);
                 let mut result = String::new();
                 for ch in chars {
                     if !is_special_char(ch) {
@@ -155,7 +249,7 @@ impl Iterator for Tokenizer<'_> {
 
 #[cfg(test)]
 mod tests {
-    use crate::reader::{ParseError, Token};
+    use crate::reader::{ParseError, Span, Token};
 
     use super::Tokenizer;
 
@@ -180,7 +274,10 @@ mod tests {
         let mut tokenizer = Tokenizer::from("\"strings");
         assert_eq!(
             tokenizer.next(),
-            Some(Err(ParseError::UnbalancedString("strings".into())))
+            Some(Err(ParseError::UnbalancedString(
+                "strings".into(),
+                Span::new(0, 8)
+            )))
         );
     }
 
@@ -189,7 +286,104 @@ mod tests {
         let mut tokenizer = Tokenizer::from("\"");
         assert_eq!(
             tokenizer.next(),
-            Some(Err(ParseError::UnbalancedEmptyString))
+            Some(Err(ParseError::UnbalancedEmptyString(Span::new(0, 1))))
+        );
+    }
+
+    #[test]
+    fn tokenize_string_escapes() {
+        let result: Vec<_> = Tokenizer::from(r#""\"quoted\"\n\t\r\\""#)
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            result,
+            vec![Token::String("\"quoted\"\n\t\r\\".to_string())]
+        );
+    }
+
+    #[test]
+    fn tokenize_unknown_escapes() {
+        let result: Vec<_> = Tokenizer::from(r#""\q\d" atom"#)
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                Token::String("\\q\\d".to_string()),
+                Token::Space,
+                Token::Atom("atom".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_set_literals() {
+        let result: Vec<_> = Tokenizer::from("#{a} #b")
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                Token::HashCurly,
+                Token::Atom("a".to_string()),
+                Token::RightCurly,
+                Token::Space,
+                Token::Atom("#b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_dispatch_macros() {
+        let result: Vec<_> = Tokenizer::from("#(a) #_b")
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                Token::HashParen,
+                Token::Atom("a".to_string()),
+                Token::RightParen,
+                Token::Space,
+                Token::HashUnderscore,
+                Token::Atom("b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_regex_literals() {
+        let result: Vec<_> = Tokenizer::from(r##"#"\d+\"" #"a""##)
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(result[0], Token::Regex(r#"\d+""#.to_string()));
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn tokenize_characters() {
+        let result: Vec<_> = Tokenizer::from(r"\a\( \newline \u03bb")
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                Token::Char('a'),
+                Token::Char('('),
+                Token::Space,
+                Token::Char('\n'),
+                Token::Space,
+                Token::Char('λ'),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_invalid_characters() {
+        let mut tokenizer = Tokenizer::from(r"\abc");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(ParseError::InvalidChar("abc".into(), Span::new(0, 4))))
         );
     }
 
@@ -0,0 +1,311 @@
+//! Seq protocol helpers and lazy sequences.
+//!
+//! Every collection, strings and nil can be split into a first item and a rest with
+//! `MalType::uncons`. Lazy sequences only compute a cell when it's first needed, so the
+//! sequences of `range`, `iterate` and `repeat` can be infinite as long as only part of them is
+//! used.
+
+use std::{rc::Rc, slice};
+
+use im_rc::Vector;
+use mal_derive::builtin_func;
+
+use crate::{
+    env::Env,
+    eval,
+    types::{
+        func::MalFuncPtr, MalClojure, MalFunc, MalHashMap, MalInt, MalLazySeq, MalList, MalNil,
+        MalSet, MalType,
+    },
+    MalError, MalResult,
+};
+
+/// Items of a sequence, realizing lazy sequences as it goes.
+#[derive(Debug)]
+pub struct SeqIter {
+    items: Vector<Rc<dyn MalType>>,
+    rest: Option<Rc<dyn MalType>>,
+}
+
+impl Iterator for SeqIter {
+    type Item = Result<Rc<dyn MalType>, MalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.pop_front() {
+                return Some(Ok(item));
+            }
+            let rest = self.rest.take()?;
+            // Lists and vectors are iterated directly instead of one `uncons` at a time
+            if let Ok(arr) = rest.as_array() {
+                self.items = arr.clone();
+                continue;
+            }
+            return match rest.uncons() {
+                Ok(Some((first, rest))) => {
+                    self.rest = Some(rest);
+                    Some(Ok(first))
+                }
+                Ok(None) => None,
+                Err(err) => Some(Err(err)),
+            };
+        }
+    }
+}
+
+pub fn iter(coll: &Rc<dyn MalType>) -> SeqIter {
+    SeqIter {
+        items: Vector::new(),
+        rest: Some(coll.clone()),
+    }
+}
+
+/// Every item of a sequence.
+pub fn to_vector(coll: &Rc<dyn MalType>) -> Result<Vector<Rc<dyn MalType>>, MalError> {
+    match coll.as_array() {
+        Ok(arr) => Ok(arr.clone()),
+        Err(_) => iter(coll).collect(),
+    }
+}
+
+/// Realize every lazy sequence in `value`, including ones nested in collections, so that errors
+/// of their bodies are returned before the value is printed or compared.
+pub fn realize(value: &Rc<dyn MalType>) -> Result<(), MalError> {
+    if let Ok(seq) = value.as_type::<MalLazySeq>() {
+        seq.realize_all()?.iter().try_for_each(realize)
+    } else if let Ok(arr) = value.as_array() {
+        arr.iter().try_for_each(realize)
+    } else if let Ok(map) = value.as_type::<MalHashMap>() {
+        map.iter().try_for_each(|(key, value)| {
+            realize(key)?;
+            realize(value)
+        })
+    } else if let Ok(set) = value.as_type::<MalSet>() {
+        set.iter().try_for_each(realize)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn is_callable(value: &dyn MalType) -> bool {
+    value.is::<MalFunc>() || value.is::<MalClojure>()
+}
+
+/// Call a builtin or a closure with `args`.
+pub fn call(func: &Rc<dyn MalType>, args: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    if let Ok(func) = func.as_type::<MalFunc>() {
+        func.call(args, env)
+    } else if let Ok(clojure) = func.as_type::<MalClojure>() {
+        clojure.apply(args)
+    } else {
+        Err(MalError::NotCallable(func.clone()))
+    }
+}
+
+fn lazy(seq: MalLazySeq) -> Rc<dyn MalType> {
+    Rc::from(seq)
+}
+
+/// Check that argument `position` of `builtin` can be called.
+pub fn function_arg(
+    func: &Rc<dyn MalType>,
+    builtin: &'static str,
+    position: usize,
+) -> Result<(), MalError> {
+    if is_callable(func.as_ref()) {
+        Ok(())
+    } else {
+        Err(MalError::WrongType("function").for_arg(builtin, position, func))
+    }
+}
+
+pub fn int_arg(
+    value: &Rc<dyn MalType>,
+    builtin: &'static str,
+    position: usize,
+) -> Result<i64, MalError> {
+    match value.as_type::<MalInt>() {
+        Ok(int) => Ok(int.value()),
+        Err(err) => Err(err.for_arg(builtin, position, value)),
+    }
+}
+
+/// `(lazy-seq body...)` evaluates the body the first time the sequence is used, the body
+/// should return a sequence.
+#[builtin_func(symbol = "lazy-seq", special)]
+pub fn lazy_seq(body: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
+    let body = body.to_vec();
+    let env = env.clone();
+    Ok(lazy(MalLazySeq::new(move || {
+        let mut value: Rc<dyn MalType> = MalNil::new();
+        for form in &body {
+            value = eval(form.clone(), &env)?;
+        }
+        value.uncons()
+    })))
+}
+
+/// Lazy sequence of `func` applied to each item of `coll`.
+pub fn map_seq(func: Rc<dyn MalType>, coll: Rc<dyn MalType>, env: Rc<Env>) -> MalLazySeq {
+    MalLazySeq::new(move || match coll.uncons()? {
+        Some((first, rest)) => {
+            let value = call(&func, slice::from_ref(&first), &env)?;
+            let rest = map_seq(func.clone(), rest, env.clone());
+            Ok(Some((value, lazy(rest))))
+        }
+        None => Ok(None),
+    })
+}
+
+/// Lazy sequence of the items of `coll` for which `pred` is truthy, or falsy when `keep` is
+/// false.
+pub fn filter_seq(
+    pred: Rc<dyn MalType>,
+    coll: Rc<dyn MalType>,
+    env: Rc<Env>,
+    keep: bool,
+) -> MalLazySeq {
+    MalLazySeq::new(move || {
+        let mut rest = coll.clone();
+        while let Some((first, next)) = rest.uncons()? {
+            if call(&pred, slice::from_ref(&first), &env)?.truthy() == keep {
+                let next = filter_seq(pred.clone(), next, env.clone(), keep);
+                return Ok(Some((first, lazy(next))));
+            }
+            rest = next;
+        }
+        Ok(None)
+    })
+}
+
+fn iterate_seq(func: Rc<dyn MalType>, value: Rc<dyn MalType>, env: Rc<Env>) -> MalLazySeq {
+    let rest = {
+        let value = value.clone();
+        MalLazySeq::new(move || {
+            let next = call(&func, slice::from_ref(&value), &env)?;
+            iterate_seq(func.clone(), next, env.clone()).realize()
+        })
+    };
+    MalLazySeq::cons(value, lazy(rest))
+}
+
+/// `(iterate f x)` is the infinite sequence `x`, `(f x)`, `(f (f x))`...
+#[builtin_func]
+pub fn iterate(func: &Rc<dyn MalType>, value: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    function_arg(func, "iterate", 1)?;
+    Ok(lazy(iterate_seq(func.clone(), value.clone(), env.clone())))
+}
+
+fn range_seq(start: i64, end: Option<i64>, step: i64) -> MalLazySeq {
+    MalLazySeq::new(move || {
+        let done = match end {
+            Some(end) if step >= 0 => start >= end,
+            Some(end) => start <= end,
+            None => false,
+        };
+        if done {
+            return Ok(None);
+        }
+        let rest = match start.checked_add(step) {
+            Some(next) => lazy(range_seq(next, end, step)),
+            None => Rc::from(MalList::new()),
+        };
+        Ok(Some((Rc::from(MalInt::from(start)), rest)))
+    })
+}
+
+/// `(range)` counts from 0 forever, `(range end)`, `(range start end)` and
+/// `(range start end step)` stop before `end`.
+#[builtin_func]
+pub fn range(
+    first: Option<&Rc<dyn MalType>>,
+    second: Option<&Rc<dyn MalType>>,
+    third: Option<&Rc<dyn MalType>>,
+) -> MalResult {
+    let (start, end, step) = match (first, second, third) {
+        (None, _, _) => (0, None, 1),
+        (Some(end), None, _) => (0, Some(int_arg(end, "range", 1)?), 1),
+        (Some(start), Some(end), step) => (
+            int_arg(start, "range", 1)?,
+            Some(int_arg(end, "range", 2)?),
+            match step {
+                Some(step) => int_arg(step, "range", 3)?,
+                None => 1,
+            },
+        ),
+    };
+    Ok(lazy(range_seq(start, end, step)))
+}
+
+fn repeat_seq(value: Rc<dyn MalType>, count: Option<i64>) -> MalLazySeq {
+    MalLazySeq::new(move || {
+        if matches!(count, Some(count) if count <= 0) {
+            return Ok(None);
+        }
+        let rest = repeat_seq(value.clone(), count.map(|count| count - 1));
+        Ok(Some((value.clone(), lazy(rest))))
+    })
+}
+
+/// `(repeat x)` repeats `x` forever, `(repeat n x)` repeats it `n` times.
+#[builtin_func]
+pub fn repeat(first: &Rc<dyn MalType>, second: Option<&Rc<dyn MalType>>) -> MalResult {
+    let seq = match second {
+        Some(value) => repeat_seq(value.clone(), Some(int_arg(first, "repeat", 1)?)),
+        None => repeat_seq(first.clone(), None),
+    };
+    Ok(lazy(seq))
+}
+
+fn take_seq(count: i64, coll: Rc<dyn MalType>) -> MalLazySeq {
+    MalLazySeq::new(move || {
+        if count <= 0 {
+            return Ok(None);
+        }
+        let cons = coll.uncons()?;
+        Ok(cons.map(|(first, rest)| (first, lazy(take_seq(count - 1, rest)))))
+    })
+}
+
+#[builtin_func]
+pub fn take(count: &MalInt, coll: &Rc<dyn MalType>) -> MalResult {
+    Ok(lazy(take_seq(count.value(), coll.clone())))
+}
+
+fn drop_seq(count: i64, coll: Rc<dyn MalType>) -> MalLazySeq {
+    MalLazySeq::new(move || {
+        let mut rest = coll.clone();
+        for _ in 0..count {
+            match rest.uncons()? {
+                Some((_, next)) => rest = next,
+                None => return Ok(None),
+            }
+        }
+        rest.uncons()
+    })
+}
+
+#[builtin_func]
+pub fn drop(count: &MalInt, coll: &Rc<dyn MalType>) -> MalResult {
+    Ok(lazy(drop_seq(count.value(), coll.clone())))
+}
+
+fn take_while_seq(pred: Rc<dyn MalType>, coll: Rc<dyn MalType>, env: Rc<Env>) -> MalLazySeq {
+    MalLazySeq::new(move || match coll.uncons()? {
+        Some((first, rest)) if call(&pred, slice::from_ref(&first), &env)?.truthy() => {
+            let rest = take_while_seq(pred.clone(), rest, env.clone());
+            Ok(Some((first, lazy(rest))))
+        }
+        Some(_) | None => Ok(None),
+    })
+}
+
+#[builtin_func(symbol = "take-while")]
+pub fn take_while(pred: &Rc<dyn MalType>, coll: &Rc<dyn MalType>, env: &Rc<Env>) -> MalResult {
+    function_arg(pred, "take-while", 1)?;
+    Ok(lazy(take_while_seq(
+        pred.clone(),
+        coll.clone(),
+        env.clone(),
+    )))
+}
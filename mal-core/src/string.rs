@@ -0,0 +1,314 @@
+//! String library, registered under the `str/` prefix.
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::Write,
+    rc::Rc,
+};
+
+use mal_derive::builtin_func;
+
+use crate::{
+    env::Env,
+    types::{
+        func::MalFuncPtr, MalBigInt, MalBool, MalChar, MalClojure, MalFunc, MalInt, MalNil,
+        MalRegex, MalString, MalType, MalVec, Number,
+    },
+    MalError, MalResult,
+};
+
+/// Text of a string or character argument, used for separators and searched values.
+fn text_arg(
+    value: &Rc<dyn MalType>,
+    builtin: &'static str,
+    position: usize,
+) -> Result<String, MalError> {
+    if let Ok(string) = value.as_type::<MalString>() {
+        Ok(string.value.clone())
+    } else if let Ok(ch) = value.as_type::<MalChar>() {
+        Ok(ch.value().to_string())
+    } else {
+        Err(MalError::WrongType("string or character").for_arg(builtin, position, value))
+    }
+}
+
+/// Byte offset of the character at `idx`, indices past the end of the string are errors.
+fn byte_offset(string: &str, idx: usize) -> Result<usize, MalError> {
+    match string.char_indices().map(|(offset, _)| offset).nth(idx) {
+        Some(offset) => Ok(offset),
+        None => {
+            let len = string.chars().count();
+            if idx == len {
+                Ok(string.len())
+            } else {
+                Err(MalError::OutOfBounds { idx, len })
+            }
+        }
+    }
+}
+
+fn char_index(value: &MalInt) -> Result<usize, MalError> {
+    match (*value).try_into() {
+        Ok(idx) => Ok(idx),
+        Err(_) => Err(MalError::NegativeIndex(value.value())),
+    }
+}
+
+#[builtin_func(symbol = "str/subs")]
+pub fn subs(string: &MalString, start: &MalInt, end: Option<&Rc<dyn MalType>>) -> MalResult {
+    let string = string.as_str();
+    let start = byte_offset(string, char_index(start)?)?;
+    let end = match end {
+        Some(end) => {
+            let end = end
+                .as_type::<MalInt>()
+                .map_err(|err| err.for_arg("str/subs", 3, end))?;
+            byte_offset(string, char_index(end)?)?
+        }
+        None => string.len(),
+    };
+    if start > end {
+        return Err(MalError::InvalidRange {
+            start: string[..start].chars().count(),
+            end: string[..end].chars().count(),
+        });
+    }
+    Ok(Rc::from(MalString::from(&string[start..end])))
+}
+
+#[builtin_func(symbol = "str/split")]
+pub fn split(string: &MalString, separator: &Rc<dyn MalType>) -> MalResult {
+    let mut parts: Vec<Rc<dyn MalType>> = if let Ok(regex) = separator.as_type::<MalRegex>() {
+        regex
+            .regex()
+            .split(string.as_str())
+            .map(|part| Rc::from(MalString::from(part)) as Rc<dyn MalType>)
+            .collect()
+    } else {
+        split_text(string, &text_arg(separator, "str/split", 2)?)
+    };
+    // Trailing empty strings are dropped, as in Clojure
+    while let Some(last) = parts.last() {
+        match last.as_type::<MalString>() {
+            Ok(last) if last.value.is_empty() => parts.pop(),
+            _ => break,
+        };
+    }
+    Ok(Rc::from(MalVec::from(parts)))
+}
+
+fn split_text(string: &MalString, separator: &str) -> Vec<Rc<dyn MalType>> {
+    if separator.is_empty() {
+        string
+            .chars()
+            .map(|ch| Rc::from(MalString::from(ch)) as Rc<dyn MalType>)
+            .collect()
+    } else {
+        string
+            .as_str()
+            .split(separator)
+            .map(|part| Rc::from(MalString::from(part)) as Rc<dyn MalType>)
+            .collect()
+    }
+}
+
+#[builtin_func(symbol = "str/join")]
+pub fn join(first: &Rc<dyn MalType>, second: Option<&Rc<dyn MalType>>) -> MalResult {
+    let (separator, collection, position) = match second {
+        Some(collection) => (first.to_string(), collection, 2),
+        None => (String::new(), first, 1),
+    };
+    let items = collection
+        .as_array()
+        .map_err(|err| err.for_arg("str/join", position, collection))?;
+    let mut result = String::new();
+    for (index, item) in items.iter().enumerate() {
+        if index != 0 {
+            result.push_str(&separator);
+        }
+        write!(result, "{}", item).unwrap();
+    }
+    Ok(Rc::from(MalString::from(result)))
+}
+
+#[builtin_func(symbol = "str/trim")]
+pub fn trim(string: &MalString) -> MalResult {
+    Ok(Rc::from(MalString::from(string.as_str().trim())))
+}
+
+#[builtin_func(symbol = "str/upper-case")]
+pub fn upper_case(string: &MalString) -> MalResult {
+    Ok(Rc::from(MalString::from(string.as_str().to_uppercase())))
+}
+
+#[builtin_func(symbol = "str/lower-case")]
+pub fn lower_case(string: &MalString) -> MalResult {
+    Ok(Rc::from(MalString::from(string.as_str().to_lowercase())))
+}
+
+#[builtin_func(symbol = "str/index-of")]
+pub fn index_of(
+    string: &MalString,
+    value: &Rc<dyn MalType>,
+    from: Option<&Rc<dyn MalType>>,
+) -> MalResult {
+    let value = text_arg(value, "str/index-of", 2)?;
+    let string = string.as_str();
+    let start = match from {
+        Some(from) => {
+            let from = from
+                .as_type::<MalInt>()
+                .map_err(|err| err.for_arg("str/index-of", 3, from))?;
+            match byte_offset(string, char_index(from)?) {
+                Ok(start) => start,
+                Err(_) => return Ok(MalNil::new()),
+            }
+        }
+        None => 0,
+    };
+    match string[start..].find(value.as_str()) {
+        Some(offset) => {
+            let idx = string[..start + offset].chars().count();
+            Ok(Rc::from(MalInt::from(idx as i64)))
+        }
+        None => Ok(MalNil::new()),
+    }
+}
+
+/// Regex patterns can be replaced with strings referring to groups as `$1`, or with the result
+/// of calling a function with each match.
+#[builtin_func(symbol = "str/replace")]
+pub fn replace(
+    string: &MalString,
+    pattern: &Rc<dyn MalType>,
+    replacement: &Rc<dyn MalType>,
+    env: &Rc<Env>,
+) -> MalResult {
+    if let Ok(regex) = pattern.as_type::<MalRegex>() {
+        return replace_regex(string.as_str(), regex, replacement, env);
+    }
+    let pattern = text_arg(pattern, "str/replace", 2)?;
+    let replacement = text_arg(replacement, "str/replace", 3)?;
+    let result = string.as_str().replace(pattern.as_str(), &replacement);
+    Ok(Rc::from(MalString::from(result)))
+}
+
+fn replace_regex(
+    string: &str,
+    regex: &MalRegex,
+    replacement: &Rc<dyn MalType>,
+    env: &Rc<Env>,
+) -> MalResult {
+    if let Ok(replacement) = replacement.as_type::<MalString>() {
+        let result = regex.regex().replace_all(string, replacement.as_str());
+        return Ok(Rc::from(MalString::from(result)));
+    }
+    let mut result = String::new();
+    let mut last = 0;
+    for (value, range) in regex.find_all(string) {
+        let args = [value];
+        let replaced = if let Ok(func) = replacement.as_type::<MalFunc>() {
+            func.call(&args, env)?
+        } else if let Ok(clojure) = replacement.as_type::<MalClojure>() {
+            clojure.apply(&args)?
+        } else {
+            let err = MalError::WrongType("string or function");
+            return Err(err.for_arg("str/replace", 3, replacement));
+        };
+        result.push_str(&string[last..range.start]);
+        write!(result, "{}", replaced).unwrap();
+        last = range.end;
+    }
+    result.push_str(&string[last..]);
+    Ok(Rc::from(MalString::from(result)))
+}
+
+#[builtin_func(symbol = "str/starts-with?")]
+pub fn starts_with(string: &MalString, prefix: &Rc<dyn MalType>) -> MalResult {
+    let prefix = text_arg(prefix, "str/starts-with?", 2)?;
+    Ok(Rc::from(MalBool::from(
+        string.as_str().starts_with(prefix.as_str()),
+    )))
+}
+
+#[builtin_func(symbol = "str/ends-with?")]
+pub fn ends_with(string: &MalString, suffix: &Rc<dyn MalType>) -> MalResult {
+    let suffix = text_arg(suffix, "str/ends-with?", 2)?;
+    Ok(Rc::from(MalBool::from(
+        string.as_str().ends_with(suffix.as_str()),
+    )))
+}
+
+#[builtin_func(symbol = "str/includes?")]
+pub fn includes(string: &MalString, value: &Rc<dyn MalType>) -> MalResult {
+    let value = text_arg(value, "str/includes?", 2)?;
+    Ok(Rc::from(MalBool::from(
+        string.as_str().contains(value.as_str()),
+    )))
+}
+
+/// Supports `%s`, `%d`, `%f` with optional precision such as `%.2f`, `%n` and `%%`.
+#[builtin_func(symbol = "str/format")]
+pub fn format(template: &MalString, args: &[Rc<dyn MalType>]) -> MalResult {
+    let mut result = String::new();
+    let mut args = args.iter().enumerate();
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            result.push(ch);
+            continue;
+        }
+        let mut precision = None;
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            let mut digits = String::new();
+            while let Some(digit) = chars.peek().filter(|ch| ch.is_ascii_digit()) {
+                digits.push(*digit);
+                chars.next();
+            }
+            match digits.parse::<usize>() {
+                Ok(digits) => precision = Some(digits),
+                Err(_) => return Err(MalError::InvalidFormat("missing precision".into())),
+            }
+        }
+        let conversion = match chars.next() {
+            Some(conversion) => conversion,
+            None => return Err(MalError::InvalidFormat("incomplete conversion".into())),
+        };
+        match conversion {
+            '%' => result.push('%'),
+            'n' => result.push('\n'),
+            's' | 'd' | 'f' => {
+                let (index, arg) = match args.next() {
+                    Some(arg) => arg,
+                    None => {
+                        let err = format!("missing argument for `%{}`", conversion);
+                        return Err(MalError::InvalidFormat(err));
+                    }
+                };
+                let position = index + 2;
+                match conversion {
+                    's' => write!(result, "{}", arg).unwrap(),
+                    'd' if arg.is::<MalInt>() || arg.is::<MalBigInt>() => {
+                        write!(result, "{}", arg).unwrap()
+                    }
+                    'd' => {
+                        let err = MalError::WrongType("integer");
+                        return Err(err.for_arg("str/format", position, arg));
+                    }
+                    _ => {
+                        let number = Number::try_from(arg.as_ref())
+                            .map_err(|err| err.for_arg("str/format", position, arg))?;
+                        let precision = precision.unwrap_or(6);
+                        write!(result, "{:.*}", precision, number.as_f64()).unwrap();
+                    }
+                }
+            }
+            _ => {
+                let err = format!("unknown conversion `%{}`", conversion);
+                return Err(MalError::InvalidFormat(err));
+            }
+        }
+    }
+    Ok(Rc::from(MalString::from(result)))
+}
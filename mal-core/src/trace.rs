@@ -0,0 +1,90 @@
+use std::{cell::RefCell, fmt::Display};
+
+use crate::reader::Location;
+
+thread_local! {
+    static CALL_STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Call to a user defined function, name is set by `def!` and location is
+/// location of the form that called the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    name: Option<String>,
+    location: Option<Location>,
+}
+
+impl Frame {
+    pub fn new(name: Option<String>, location: Option<Location>) -> Self {
+        Self { name, location }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.name().unwrap_or("<anonymous>");
+        match &self.location {
+            Some(location) => write!(f, "{} ({})", name, location),
+            None => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Snapshot of the call stack, innermost call comes first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StackTrace {
+    frames: Vec<Frame>,
+}
+
+impl StackTrace {
+    pub fn capture() -> Self {
+        let frames = CALL_STACK.with(|stack| stack.borrow().iter().rev().cloned().collect());
+        Self { frames }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+}
+
+impl Display for StackTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut iter = self.frames.iter();
+        match iter.next() {
+            Some(frame) => write!(f, "  at {}", frame)?,
+            None => return Ok(()),
+        }
+        for frame in iter {
+            write!(f, "\n  at {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn push(frame: Frame) {
+    CALL_STACK.with(|stack| stack.borrow_mut().push(frame));
+}
+
+pub fn pop() {
+    CALL_STACK.with(|stack| stack.borrow_mut().pop());
+}
+
+/// Replace the innermost frame, used for tail calls.
+pub fn replace(frame: Frame) {
+    CALL_STACK.with(|stack| match stack.borrow_mut().last_mut() {
+        Some(last) => *last = frame,
+        None => unreachable!("tail call without a frame"),
+    });
+}
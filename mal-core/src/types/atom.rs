@@ -2,10 +2,12 @@ use std::{
     any::Any,
     cell::RefCell,
     fmt::{self, Debug, Display},
+    hash::Hasher,
+    ptr,
     rc::Rc,
 };
 
-use crate::{env::Env, eval, MalResult};
+use crate::{env::Env, MalResult};
 
 use super::{MalClojure, MalFunc, MalType};
 
@@ -42,15 +44,9 @@ impl MalAtom {
         Ok(new_value)
     }
 
-    pub fn update_with_clojure(
-        &self,
-        clojure: &MalClojure,
-        args: &[Rc<dyn MalType>],
-        env: &Rc<Env>,
-    ) -> MalResult {
+    pub fn update_with_clojure(&self, clojure: &MalClojure, args: &[Rc<dyn MalType>]) -> MalResult {
         let args = self.update_args(args);
-        let (new_ast, new_env) = clojure.call(&args, env)?;
-        let new_value = eval(new_ast, &new_env)?;
+        let new_value = clojure.apply(&args)?;
         self.value.replace(new_value.clone());
         Ok(new_value)
     }
@@ -82,6 +78,10 @@ impl Display for MalAtom {
 }
 
 impl MalType for MalAtom {
+    fn type_name() -> &'static str {
+        "atom"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -91,9 +91,14 @@ impl MalType for MalAtom {
     }
 
     fn equal(&self, rhs: &dyn MalType) -> bool {
+        // Atoms are mutable so two atoms are equal only if they are the same atom
         match rhs.as_type::<MalAtom>() {
-            Ok(rhs) => self.value.borrow().equal(rhs.value.borrow().as_ref()),
+            Ok(rhs) => ptr::eq(self, rhs),
             Err(_) => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        ptr::hash(self, &mut state);
+    }
 }
@@ -0,0 +1,76 @@
+use std::{
+    any::Any,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+};
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use super::{MalInt, MalType};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct MalBigInt {
+    value: BigInt,
+}
+
+impl<T> From<T> for MalBigInt
+where
+    T: Into<BigInt>,
+{
+    fn from(value: T) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl MalBigInt {
+    pub fn value(&self) -> &BigInt {
+        &self.value
+    }
+}
+
+impl Debug for MalBigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}N", self.value)
+    }
+}
+
+impl Display for MalBigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl MalType for MalBigInt {
+    fn type_name() -> &'static str {
+        "big integer"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        if let Ok(int) = rhs.as_type::<MalInt>() {
+            self.value == BigInt::from(int.value())
+        } else if let Ok(bigint) = rhs.as_type::<Self>() {
+            self.value == bigint.value
+        } else {
+            false
+        }
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        // Big integers that fit into `i64` are equal to integers so they should hash the same
+        match self.value.to_i64() {
+            Some(value) => value.hash(&mut state),
+            None => self.value.hash(&mut state),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
 };
 
 use super::MalType;
@@ -39,6 +40,10 @@ impl MalBool {
 }
 
 impl MalType for MalBool {
+    fn type_name() -> &'static str {
+        "boolean"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -53,4 +58,8 @@ impl MalType for MalBool {
             Err(_) => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.value.hash(&mut state);
+    }
 }
@@ -0,0 +1,91 @@
+use std::{
+    any::Any,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+};
+
+use super::MalType;
+
+const NAMES: &[(&str, char)] = &[
+    ("newline", '\n'),
+    ("space", ' '),
+    ("tab", '\t'),
+    ("return", '\r'),
+    ("backspace", '\u{8}'),
+    ("formfeed", '\u{c}'),
+];
+
+#[derive(Clone, Copy)]
+pub struct MalChar {
+    value: char,
+}
+
+impl From<char> for MalChar {
+    fn from(value: char) -> Self {
+        Self { value }
+    }
+}
+
+impl Debug for MalChar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match NAMES.iter().find(|(_, ch)| *ch == self.value) {
+            Some((name, _)) => write!(f, "\\{}", name),
+            None => write!(f, "\\{}", self.value),
+        }
+    }
+}
+
+impl Display for MalChar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl MalChar {
+    /// Character for the text after `\` in a literal, either a single character, a name such
+    /// as `newline` or a unicode escape such as `u03bb`.
+    pub fn from_literal(literal: &str) -> Option<char> {
+        let mut chars = literal.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            return Some(ch);
+        }
+        if let Some((_, ch)) = NAMES.iter().find(|(name, _)| *name == literal) {
+            return Some(*ch);
+        }
+        match literal.strip_prefix('u') {
+            Some(hex) if hex.len() == 4 => {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> char {
+        self.value
+    }
+}
+
+impl MalType for MalChar {
+    fn type_name() -> &'static str {
+        "character"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        match rhs.as_type::<Self>() {
+            Ok(rhs) => self.value == rhs.value,
+            Err(_) => false,
+        }
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.value.hash(&mut state);
+    }
+}
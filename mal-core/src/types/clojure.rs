@@ -2,18 +2,116 @@ use std::{
     any::Any,
     cell::RefCell,
     fmt::{Debug, Display},
+    hash::Hasher,
+    ptr,
     rc::Rc,
 };
 
-use crate::{env::Env, eval, MalError, MalResult};
+use im_rc::Vector;
+
+use crate::{
+    destructure::bind,
+    env::Env,
+    eval_in_target,
+    trace::{self, Frame},
+    MalError, MalResult, RecurTarget,
+};
 
 use super::{MalList, MalSymbol, MalType};
 
-pub struct MalClojure {
-    arg_symbols: Vec<Rc<dyn MalType>>,
+/// Parameters and body of one overload of a function, also used for the bindings of `loop*`.
+#[derive(Debug)]
+pub struct Arity {
+    fixed: Vec<Rc<dyn MalType>>,
+    rest: Option<Rc<dyn MalType>>,
     body: Rc<dyn MalType>,
+}
+
+impl Arity {
+    /// Parameters are binding forms, optionally followed by `&` and a form for the rest of the
+    /// arguments.
+    pub fn try_new(
+        params: &Vector<Rc<dyn MalType>>,
+        body: Rc<dyn MalType>,
+    ) -> Result<Self, MalError> {
+        let mut fixed = Vec::with_capacity(params.len());
+        let mut rest = None;
+        let mut params = params.iter();
+        while let Some(param) = params.next() {
+            if param.is_special("&") {
+                match (params.next(), params.next()) {
+                    (Some(param), None) => rest = Some(param.clone()),
+                    _ => return Err(MalError::InvalidBinding(param.clone())),
+                }
+            } else {
+                fixed.push(param.clone());
+            }
+        }
+        Ok(Self { fixed, rest, body })
+    }
+
+    /// Number of arguments without the rest arguments.
+    pub fn required(&self) -> usize {
+        self.fixed.len()
+    }
+
+    pub fn is_variadic(&self) -> bool {
+        self.rest.is_some()
+    }
+
+    pub fn body(&self) -> Rc<dyn MalType> {
+        self.body.clone()
+    }
+
+    /// Environment with parameters bound to `args`, arguments past the fixed ones are collected
+    /// into a list for the rest parameter.
+    pub fn bind(&self, args: &[Rc<dyn MalType>], outer: &Rc<Env>) -> Result<Rc<Env>, MalError> {
+        if args.len() < self.required() || (args.len() > self.required() && !self.is_variadic()) {
+            return Err(MalError::WrongArity {
+                name: String::from("fn*"),
+                expected: match self.is_variadic() {
+                    true => format!("at least {}", self.required()),
+                    false => self.required().to_string(),
+                },
+                actual: args.len(),
+            });
+        }
+        let env = Env::with_outer(outer.clone());
+        for (param, arg) in self.fixed.iter().zip(args) {
+            bind(param, arg.clone(), &env)?;
+        }
+        if let Some(rest) = &self.rest {
+            let list: MalList = args[self.required()..].iter().collect();
+            bind(rest, Rc::from(list), &env)?;
+        }
+        Ok(env)
+    }
+
+    /// Environment with parameters bound to `values` from `recur`, the rest parameter is bound
+    /// to the value given in its place.
+    pub fn rebind(&self, values: &[Rc<dyn MalType>], outer: &Rc<Env>) -> Result<Rc<Env>, MalError> {
+        let expected = self.required() + self.is_variadic() as usize;
+        if values.len() != expected {
+            return Err(MalError::WrongArity {
+                name: String::from("recur"),
+                expected: expected.to_string(),
+                actual: values.len(),
+            });
+        }
+        let env = Env::with_outer(outer.clone());
+        for (param, value) in self.fixed.iter().chain(&self.rest).zip(values) {
+            bind(param, value.clone(), &env)?;
+        }
+        Ok(env)
+    }
+}
+
+pub struct MalClojure {
+    arities: Vec<Rc<Arity>>,
     outer: Rc<Env>,
     is_macro: RefCell<bool>,
+    name: RefCell<Option<String>>,
+    meta: Option<Rc<dyn MalType>>,
 }
 
 impl Debug for MalClojure {
@@ -37,6 +135,10 @@ impl Display for MalClojure {
 }
 
 impl MalType for MalClojure {
+    fn type_name() -> &'static str {
+        "function"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -45,27 +147,58 @@ impl MalType for MalClojure {
         self
     }
 
-    fn equal(&self, _rhs: &dyn MalType) -> bool {
-        todo!()
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        match rhs.as_type::<Self>() {
+            Ok(rhs) => ptr::eq(self, rhs),
+            Err(_) => false,
+        }
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        ptr::hash(self, &mut state);
     }
 }
 
 impl MalClojure {
-    pub fn try_new(args: &[Rc<dyn MalType>], body: Rc<dyn MalType>, outer: Rc<Env>) -> MalResult {
-        for arg in args {
-            if !arg.is::<MalSymbol>() {
-                return Err(MalError::TypeError);
+    /// Function with an overload for each of `arities`, fixed arities should take different
+    /// number of arguments and the variadic one, if any, at least as many as each of them.
+    pub fn try_new(arities: Vec<Arity>, outer: Rc<Env>) -> MalResult {
+        let mut variadic = arities.iter().filter(|arity| arity.is_variadic());
+        if let (Some(_), Some(_)) = (variadic.next(), variadic.next()) {
+            return Err(MalError::InvalidFn(
+                "can't have more than one variadic overload",
+            ));
+        }
+        for (index, arity) in arities.iter().enumerate() {
+            for other in &arities[index + 1..] {
+                if !arity.is_variadic()
+                    && !other.is_variadic()
+                    && arity.required() == other.required()
+                {
+                    return Err(MalError::InvalidFn(
+                        "can't have two overloads with the same arity",
+                    ));
+                }
+                let (fixed, variadic) = match (arity.is_variadic(), other.is_variadic()) {
+                    (true, false) => (other, arity),
+                    (false, true) => (arity, other),
+                    _ => continue,
+                };
+                if fixed.required() > variadic.required() {
+                    return Err(MalError::InvalidFn(
+                        "can't have fixed arity overload with more params than variadic overload",
+                    ));
+                }
             }
         }
-        let arg_symbols = Vec::from(args);
         Ok(Rc::from(Self {
-            arg_symbols,
-            body,
+            arities: arities.into_iter().map(Rc::from).collect(),
             outer,
             is_macro: false.into(),
+            name: None.into(),
+            meta: None,
         }))
     }
-
     pub fn set_macro(&self) {
         *self.is_macro.borrow_mut() = true;
     }
@@ -73,51 +206,97 @@ impl MalClojure {
     pub fn is_macro(&self) -> bool {
         *self.is_macro.borrow()
     }
+
+    /// Name the function is reported under in backtraces, only the first
+    /// name it is defined with is kept.
+    pub fn set_name(&self, name: &MalSymbol) {
+        self.name
+            .borrow_mut()
+            .get_or_insert_with(|| name.to_string());
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.borrow().clone()
+    }
+
+    pub fn meta(&self) -> Option<&Rc<dyn MalType>> {
+        self.meta.as_ref()
+    }
+
+    pub fn with_meta(&self, meta: Option<Rc<dyn MalType>>) -> Self {
+        Self {
+            arities: self.arities.clone(),
+            outer: self.outer.clone(),
+            is_macro: self.is_macro.clone(),
+            name: self.name.clone(),
+            meta,
+        }
+    }
 }
 
 impl MalClojure {
-    pub fn call(
-        &self,
-        arg_exprs: &[Rc<dyn MalType>],
-        env: &Rc<Env>,
-    ) -> Result<(Rc<dyn MalType>, Rc<Env>), MalError> {
-        let current = Env::with_outer(self.outer.clone());
-
-        for i in 0..self.arg_symbols.len() {
-            let symbol = match self.arg_symbols.get(i) {
-                Some(symbol) => symbol.as_type::<MalSymbol>()?,
-                None => return Err(MalError::TypeError),
-            };
-            if symbol == "&" {
-                // If current symbol is `&` then next symbol should capture rest of expressions as list
-                let symbol = match self.arg_symbols.get(i + 1) {
-                    Some(symbol) => symbol.as_type::<MalSymbol>()?,
-                    None => return Err(MalError::TypeError),
-                };
-                let value = if self.is_macro() {
-                    let variadic: MalList = arg_exprs[i..].iter().collect();
-                    Rc::from(variadic)
-                } else {
-                    MalClojure::get_variadic_args(&arg_exprs[i..], env)?
-                };
-                current.set(symbol, value);
-                break;
-            } else {
-                let value = match arg_exprs.get(i) {
-                    Some(expr) => expr.clone(),
-                    None => return Err(MalError::TypeError),
-                };
-                current.set(symbol, value);
-            }
-        }
-        Ok((self.body.clone(), current))
+    /// Overload taking `count` arguments.
+    pub fn arity(&self, count: usize) -> Result<&Rc<Arity>, MalError> {
+        let fixed = self
+            .arities
+            .iter()
+            .find(|arity| !arity.is_variadic() && arity.required() == count);
+        let variadic = || {
+            self.arities
+                .iter()
+                .find(|arity| arity.is_variadic() && arity.required() <= count)
+        };
+        fixed.or_else(variadic).ok_or_else(|| MalError::WrongArity {
+            name: self.name().unwrap_or_else(|| String::from("<anonymous>")),
+            expected: self.expected_arity(),
+            actual: count,
+        })
     }
 
-    fn get_variadic_args(slice: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
-        let mut vector = Vec::with_capacity(slice.len());
-        for expr in slice {
-            vector.push(eval(expr.clone(), env)?);
+    /// Argument counts the overloads take, such as `1, 2 or at least 4`.
+    fn expected_arity(&self) -> String {
+        let mut counts: Vec<_> = self
+            .arities
+            .iter()
+            .map(|arity| (arity.is_variadic(), arity.required()))
+            .collect();
+        counts.sort_unstable();
+        let mut counts: Vec<_> = counts
+            .into_iter()
+            .map(|(variadic, required)| match variadic {
+                true => format!("at least {}", required),
+                false => required.to_string(),
+            })
+            .collect();
+        match counts.pop() {
+            Some(last) if counts.is_empty() => last,
+            Some(last) => format!("{} or {}", counts.join(", "), last),
+            None => String::from("0"),
         }
-        Ok(Rc::from(MalList::from(vector)))
+    }
+
+    pub fn outer(&self) -> &Rc<Env> {
+        &self.outer
+    }
+
+    /// Body of the overload matching `args` and the environment to evaluate it in.
+    pub fn call(&self, args: &[Rc<dyn MalType>]) -> Result<(Rc<dyn MalType>, Rc<Env>), MalError> {
+        let arity = self.arity(args.len())?;
+        Ok((arity.body(), arity.bind(args, &self.outer)?))
+    }
+
+    /// Call the function outside of `eval`, used by builtins taking functions
+    /// as arguments.
+    pub fn apply(&self, args: &[Rc<dyn MalType>]) -> MalResult {
+        let arity = self.arity(args.len())?;
+        let env = arity.bind(args, &self.outer)?;
+        let target = RecurTarget {
+            arity: arity.clone(),
+            outer: self.outer.clone(),
+        };
+        trace::push(Frame::new(self.name(), None));
+        let result = eval_in_target(arity.body(), &env, Some(target)).map_err(MalError::traced);
+        trace::pop();
+        result
     }
 }
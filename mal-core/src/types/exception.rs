@@ -1,6 +1,8 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    mem,
 };
 
 use crate::MalError;
@@ -18,19 +20,29 @@ impl From<MalError> for MalException {
     }
 }
 
+impl MalException {
+    pub fn error(&self) -> &MalError {
+        &self.value
+    }
+}
+
 impl Debug for MalException {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "\"{}\"", self.value)
+        write!(f, "\"{}\"", self.value.inner())
     }
 }
 
 impl Display for MalException {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.value)
+        write!(f, "{}", self.value.inner())
     }
 }
 
 impl MalType for MalException {
+    fn type_name() -> &'static str {
+        "exception"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -45,4 +57,8 @@ impl MalType for MalException {
             Err(_) => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        mem::discriminant(self.value.inner()).hash(&mut state);
+    }
 }
@@ -0,0 +1,101 @@
+use std::{
+    any::Any,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    ops::{Add, Div, Mul, Sub},
+};
+
+use super::MalType;
+
+#[derive(PartialEq, PartialOrd, Clone, Copy)]
+pub struct MalFloat {
+    value: f64,
+}
+
+impl<T> From<T> for MalFloat
+where
+    T: Into<f64>,
+{
+    fn from(value: T) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl MalFloat {
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl Debug for MalFloat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl Display for MalFloat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl MalType for MalFloat {
+    fn type_name() -> &'static str {
+        "float"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        match rhs.as_type::<Self>() {
+            Ok(float) => self.value == float.value,
+            Err(_) => false,
+        }
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        // `0.0` and `-0.0` are equal but have different bits
+        let value = if self.value == 0.0 { 0.0 } else { self.value };
+        value.to_bits().hash(&mut state);
+    }
+}
+
+impl Add for &MalFloat {
+    type Output = MalFloat;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        (self.value + rhs.value).into()
+    }
+}
+
+impl Sub for &MalFloat {
+    type Output = MalFloat;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        (self.value - rhs.value).into()
+    }
+}
+
+impl Mul for &MalFloat {
+    type Output = MalFloat;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        (self.value * rhs.value).into()
+    }
+}
+
+impl Div for &MalFloat {
+    type Output = MalFloat;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        (self.value / rhs.value).into()
+    }
+}
@@ -1,6 +1,8 @@
 use std::{
     any::Any,
+    borrow::Cow,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
     rc::Rc,
 };
 
@@ -10,9 +12,17 @@ use super::MalType;
 
 pub type MalFuncPtr = dyn Fn(&[Rc<dyn MalType>], &Rc<Env>) -> MalResult;
 
+/// Builtin defined with `#[builtin_func]` or a closure registered by the host at runtime.
+#[derive(Clone)]
+enum FuncPtr {
+    Static(&'static MalFuncPtr),
+    Closure(Rc<MalFuncPtr>),
+}
+
 pub struct MalFunc {
-    name: &'static str,
-    ptr: &'static MalFuncPtr,
+    name: Cow<'static, str>,
+    ptr: FuncPtr,
+    meta: Option<Rc<dyn MalType>>,
 }
 
 impl Debug for MalFunc {
@@ -28,6 +38,10 @@ impl Display for MalFunc {
 }
 
 impl MalType for MalFunc {
+    fn type_name() -> &'static str {
+        "function"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -37,20 +51,64 @@ impl MalType for MalFunc {
     }
 
     fn equal(&self, rhs: &dyn MalType) -> bool {
-        match rhs.as_type::<Self>() {
-            Ok(func) => self.name == func.name,
-            Err(_) => false,
+        let func = match rhs.as_type::<Self>() {
+            Ok(func) => func,
+            Err(_) => return false,
+        };
+        match (&self.ptr, &func.ptr) {
+            (FuncPtr::Static(_), FuncPtr::Static(_)) => self.name == func.name,
+            // Closures with the same name may capture different state
+            (FuncPtr::Closure(lhs), FuncPtr::Closure(rhs)) => Rc::ptr_eq(lhs, rhs),
+            _ => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.name.hash(&mut state);
+    }
 }
 
 impl MalFunc {
     pub fn new(name: &'static str, ptr: &'static MalFuncPtr) -> Self {
-        Self { name, ptr }
+        Self {
+            name: Cow::Borrowed(name),
+            ptr: FuncPtr::Static(ptr),
+            meta: None,
+        }
+    }
+
+    /// Function calling `func`, which can capture state of the host application.
+    pub fn from_closure<F>(name: impl Into<String>, func: F) -> Self
+    where
+        F: Fn(&[Rc<dyn MalType>], &Rc<Env>) -> MalResult + 'static,
+    {
+        Self {
+            name: Cow::Owned(name.into()),
+            ptr: FuncPtr::Closure(Rc::new(func)),
+            meta: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn meta(&self) -> Option<&Rc<dyn MalType>> {
+        self.meta.as_ref()
+    }
+
+    pub fn with_meta(&self, meta: Option<Rc<dyn MalType>>) -> Self {
+        Self {
+            name: self.name.clone(),
+            ptr: self.ptr.clone(),
+            meta,
+        }
     }
 
     pub fn call(&self, args: &[Rc<dyn MalType>], env: &Rc<Env>) -> MalResult {
-        let func = self.ptr;
-        func(args, env)
+        match &self.ptr {
+            FuncPtr::Static(func) => func(args, env),
+            FuncPtr::Closure(func) => func(args, env),
+        }
     }
 }
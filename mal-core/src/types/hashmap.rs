@@ -1,26 +1,30 @@
 use std::{
     any::Any,
-    collections::{
-        hash_map::{Iter, Keys, Values},
-        HashMap,
-    },
+    collections::hash_map::DefaultHasher,
     convert::TryFrom,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
     rc::Rc,
 };
 
+use im_rc::{
+    hashmap::{Iter, Keys, Values},
+    HashMap,
+};
+
 use crate::MalError;
 
-use super::{MalKeyword, MalString, MalType};
+use super::{array_uncons, Cons, MalType, MalVec};
 
 #[derive(Default, Clone)]
 pub struct MalHashMap {
-    value: HashMap<String, Rc<dyn MalType>>,
+    value: HashMap<Rc<dyn MalType>, Rc<dyn MalType>>,
+    meta: Option<Rc<dyn MalType>>,
 }
 
-impl From<HashMap<String, Rc<dyn MalType>>> for MalHashMap {
-    fn from(value: HashMap<String, Rc<dyn MalType>>) -> Self {
-        MalHashMap { value }
+impl From<HashMap<Rc<dyn MalType>, Rc<dyn MalType>>> for MalHashMap {
+    fn from(value: HashMap<Rc<dyn MalType>, Rc<dyn MalType>>) -> Self {
+        MalHashMap { value, meta: None }
     }
 }
 
@@ -28,8 +32,12 @@ impl TryFrom<Vec<Rc<dyn MalType>>> for MalHashMap {
     type Error = MalError;
 
     fn try_from(value: Vec<Rc<dyn MalType>>) -> Result<Self, Self::Error> {
-        if value.len() % 2 != 0 {
-            return Err(MalError::TypeError);
+        if !value.len().is_multiple_of(2) {
+            return Err(MalError::WrongArity {
+                name: String::from("hash-map"),
+                expected: String::from("an even number of"),
+                actual: value.len(),
+            });
         }
         Self::try_from_iter(value.into_iter())
     }
@@ -40,16 +48,11 @@ impl Debug for MalHashMap {
         write!(f, "{{")?;
         let mut iter = self.value.iter();
         match iter.next() {
-            Some((key, value)) if key.starts_with(':') => write!(f, "{} {:?}", key, value)?,
             Some((key, value)) => write!(f, "{:?} {:?}", key, value)?,
             None => return write!(f, "}}"),
         }
         for (key, value) in iter {
-            if key.starts_with(':') {
-                write!(f, " {} {:?}", key, value)?;
-            } else {
-                write!(f, " {:?} {:?}", key, value)?;
-            }
+            write!(f, " {:?} {:?}", key, value)?;
         }
         write!(f, "}}")
     }
@@ -75,9 +78,14 @@ impl MalHashMap {
         Self::default()
     }
 
-    pub fn with_capacity(capacity: usize) -> Self {
+    pub fn meta(&self) -> Option<&Rc<dyn MalType>> {
+        self.meta.as_ref()
+    }
+
+    pub fn with_meta(&self, meta: Option<Rc<dyn MalType>>) -> Self {
         Self {
-            value: HashMap::with_capacity(capacity),
+            value: self.value.clone(),
+            meta,
         }
     }
 
@@ -89,7 +97,7 @@ impl MalHashMap {
         self.value.len()
     }
 
-    pub fn iter(&self) -> Iter<'_, String, Rc<dyn MalType>> {
+    pub fn iter(&self) -> Iter<'_, Rc<dyn MalType>, Rc<dyn MalType>> {
         self.value.iter()
     }
 
@@ -97,11 +105,7 @@ impl MalHashMap {
     where
         T: Iterator<Item = Rc<dyn MalType>>,
     {
-        let mut result = if let (_, Some(upper)) = iter.size_hint() {
-            Self::with_capacity(upper / 2)
-        } else {
-            MalHashMap::new()
-        };
+        let mut result = MalHashMap::new();
         result.insert_mut(iter)?;
         Ok(result)
     }
@@ -110,13 +114,15 @@ impl MalHashMap {
     where
         T: Iterator<Item = Rc<dyn MalType>>,
     {
-        while let Some(item) = iter.next() {
-            if item.is::<MalString>() && item.is::<MalKeyword>() {
-                return Err(MalError::TypeError);
-            }
-            let key = item.to_string();
+        while let Some(key) = iter.next() {
             let value = iter.next().unwrap();
-            self.value.insert(key, value.clone());
+            // An equal key that is already present is kept, only its value is replaced
+            match self.value.get_mut(key.as_ref()) {
+                Some(entry) => *entry = value,
+                None => {
+                    self.value.insert(key, value);
+                }
+            }
         }
         Ok(())
     }
@@ -125,15 +131,7 @@ impl MalHashMap {
     where
         T: Iterator<Item = Rc<dyn MalType>>,
     {
-        let initial_capacity = if let (_, Some(upper)) = iter.size_hint() {
-            self.len() + upper
-        } else {
-            self.len()
-        };
-        let mut result = Self::with_capacity(initial_capacity);
-        for (k, v) in &self.value {
-            result.value.insert(k.clone(), v.clone());
-        }
+        let mut result = self.clone();
         result.insert_mut(iter)?;
         Ok(result)
     }
@@ -143,34 +141,34 @@ impl MalHashMap {
         T: Iterator<Item = &'a Rc<dyn MalType>>,
     {
         let mut result = self.clone();
-        for item in iter {
-            if let Ok(string) = item.as_type::<MalString>() {
-                result.value.remove(&string.value);
-            } else if let Ok(keyword) = item.as_type::<MalKeyword>() {
-                result.value.remove(&keyword.value);
-            }
+        for key in iter {
+            result.value.remove(key.as_ref());
         }
         Ok(result)
     }
 
-    pub fn get(&self, key: &str) -> Option<&Rc<dyn MalType>> {
+    pub fn get(&self, key: &dyn MalType) -> Option<&Rc<dyn MalType>> {
         self.value.get(key)
     }
 
-    pub fn contains(&self, key: &str) -> bool {
+    pub fn contains(&self, key: &dyn MalType) -> bool {
         self.value.contains_key(key)
     }
 
-    pub fn keys(&self) -> Keys<'_, String, Rc<dyn MalType>> {
+    pub fn keys(&self) -> Keys<'_, Rc<dyn MalType>, Rc<dyn MalType>> {
         self.value.keys()
     }
 
-    pub fn values(&self) -> Values<'_, String, Rc<dyn MalType>> {
+    pub fn values(&self) -> Values<'_, Rc<dyn MalType>, Rc<dyn MalType>> {
         self.value.values()
     }
 }
 
 impl MalType for MalHashMap {
+    fn type_name() -> &'static str {
+        "hash-map"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -179,7 +177,41 @@ impl MalType for MalHashMap {
         self
     }
 
-    fn equal(&self, _rhs: &dyn MalType) -> bool {
-        false
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        let rhs = match rhs.as_type::<Self>() {
+            Ok(rhs) => rhs,
+            Err(_) => return false,
+        };
+        self.len() == rhs.len()
+            && self.iter().all(|(key, value)| match rhs.get(key.as_ref()) {
+                Some(rhs_value) => value.equal(rhs_value.as_ref()),
+                None => false,
+            })
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        // Entries are hashed separately and combined so that the order of iteration doesn't
+        // change the hash
+        let mut combined: u64 = 0;
+        for (key, value) in self.iter() {
+            let mut hasher = DefaultHasher::new();
+            MalType::hash(key.as_ref(), &mut hasher);
+            MalType::hash(value.as_ref(), &mut hasher);
+            combined = combined.wrapping_add(hasher.finish());
+        }
+        self.len().hash(&mut state);
+        combined.hash(&mut state);
+    }
+
+    /// Entries are seen as `[key value]` vectors.
+    fn uncons(&self) -> Result<Cons, MalError> {
+        let entries = self
+            .iter()
+            .map(|(key, value)| {
+                let entry = MalVec::from(vec![key.clone(), value.clone()]);
+                Rc::from(entry) as Rc<dyn MalType>
+            })
+            .collect();
+        Ok(array_uncons(&entries))
     }
 }
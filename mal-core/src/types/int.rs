@@ -2,11 +2,13 @@ use std::{
     any::Any,
     convert::TryInto,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
     num::TryFromIntError,
-    ops::{Add, Div, Mul, Sub},
 };
 
-use super::MalType;
+use num_bigint::BigInt;
+
+use super::{MalBigInt, MalType};
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct MalInt {
@@ -24,6 +26,12 @@ where
     }
 }
 
+impl MalInt {
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+}
+
 impl TryInto<u64> for MalInt {
     type Error = TryFromIntError;
 
@@ -52,6 +60,10 @@ impl Display for MalInt {
 }
 
 impl MalType for MalInt {
+    fn type_name() -> &'static str {
+        "integer"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -61,41 +73,16 @@ impl MalType for MalInt {
     }
 
     fn equal(&self, rhs: &dyn MalType) -> bool {
-        match rhs.as_type::<Self>() {
-            Ok(int) => self.value == int.value,
-            Err(_) => false,
+        if let Ok(int) = rhs.as_type::<Self>() {
+            self.value == int.value
+        } else if let Ok(bigint) = rhs.as_type::<MalBigInt>() {
+            &BigInt::from(self.value) == bigint.value()
+        } else {
+            false
         }
     }
-}
-
-impl Add for &MalInt {
-    type Output = MalInt;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        (self.value + rhs.value).into()
-    }
-}
-
-impl Sub for &MalInt {
-    type Output = MalInt;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        (self.value - rhs.value).into()
-    }
-}
-
-impl Mul for &MalInt {
-    type Output = MalInt;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        (self.value * rhs.value).into()
-    }
-}
-
-impl Div for &MalInt {
-    type Output = MalInt;
 
-    fn div(self, rhs: Self) -> Self::Output {
-        (self.value / rhs.value).into()
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.value.hash(&mut state);
     }
 }
@@ -1,6 +1,7 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
 };
 
 use super::MalType;
@@ -33,6 +34,10 @@ impl Display for MalKeyword {
 }
 
 impl MalType for MalKeyword {
+    fn type_name() -> &'static str {
+        "keyword"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -47,4 +52,8 @@ impl MalType for MalKeyword {
             Err(_) => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.value.hash(&mut state);
+    }
 }
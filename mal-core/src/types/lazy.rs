@@ -0,0 +1,161 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt::{self, Debug, Display},
+    hash::Hasher,
+    mem,
+    rc::Rc,
+};
+
+use im_rc::Vector;
+
+use crate::{seq, MalError};
+
+use super::{array_equal, array_hash, Cons, MalType};
+
+type Thunk = Rc<dyn Fn() -> Result<Cons, MalError>>;
+
+enum State {
+    Pending(Thunk),
+    Realized(Cons),
+}
+
+/// Sequence whose items are computed the first time they're needed, one cell at a time.
+pub struct MalLazySeq {
+    state: RefCell<State>,
+}
+
+impl MalLazySeq {
+    /// Sequence computing its first item and rest with `thunk` when first needed.
+    pub fn new<F>(thunk: F) -> Self
+    where
+        F: Fn() -> Result<Cons, MalError> + 'static,
+    {
+        Self {
+            state: RefCell::from(State::Pending(Rc::new(thunk))),
+        }
+    }
+
+    /// Already realized sequence of `first` followed by `rest`.
+    pub fn cons(first: Rc<dyn MalType>, rest: Rc<dyn MalType>) -> Self {
+        Self {
+            state: RefCell::from(State::Realized(Some((first, rest)))),
+        }
+    }
+
+    /// First item and rest, running the thunk if it hasn't run yet. A thunk that fails runs
+    /// again the next time the sequence is realized.
+    pub fn realize(&self) -> Result<Cons, MalError> {
+        let thunk = match &*self.state.borrow() {
+            State::Realized(cons) => return Ok(cons.clone()),
+            State::Pending(thunk) => thunk.clone(),
+        };
+        let cons = thunk()?;
+        self.state.replace(State::Realized(cons.clone()));
+        Ok(cons)
+    }
+
+    /// Every item of the sequence, which never returns for infinite sequences.
+    pub fn realize_all(&self) -> Result<Vector<Rc<dyn MalType>>, MalError> {
+        let mut items = Vector::new();
+        if let Some((first, rest)) = self.realize()? {
+            items.push_back(first);
+            for item in seq::iter(&rest) {
+                items.push_back(item?);
+            }
+        }
+        Ok(items)
+    }
+
+    fn write_items(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        write_item: fn(&Rc<dyn MalType>, &mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        // Printing builtins realize sequences first and report errors, a sequence that still
+        // fails here is only shown as unrealized
+        let items = match self.realize_all() {
+            Ok(items) => items,
+            Err(_) => return write!(f, "#<lazy-seq>"),
+        };
+        write!(f, "(")?;
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write_item(item, f)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Drop for MalLazySeq {
+    fn drop(&mut self) {
+        // Cells of long realized sequences are unlinked in a loop, dropping them recursively
+        // could overflow the stack
+        let mut rest = match mem::replace(self.state.get_mut(), State::Realized(None)) {
+            State::Realized(Some((_, rest))) => rest,
+            State::Realized(None) | State::Pending(_) => return,
+        };
+        while let Some(next) = Rc::get_mut(&mut rest)
+            .and_then(|rest| rest.as_any_mut().downcast_mut::<MalLazySeq>())
+            .and_then(
+                |next| match mem::replace(next.state.get_mut(), State::Realized(None)) {
+                    State::Realized(Some((_, next))) => Some(next),
+                    State::Realized(None) | State::Pending(_) => None,
+                },
+            )
+        {
+            rest = next;
+        }
+    }
+}
+
+impl Debug for MalLazySeq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_items(f, |item, f| write!(f, "{:?}", item))
+    }
+}
+
+impl Display for MalLazySeq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_items(f, |item, f| write!(f, "{}", item))
+    }
+}
+
+impl MalType for MalLazySeq {
+    fn type_name() -> &'static str {
+        "lazy-seq"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        let items = match self.realize_all() {
+            Ok(items) => items,
+            Err(_) => return false,
+        };
+        if let Ok(rhs) = rhs.as_type::<Self>() {
+            matches!(rhs.realize_all(), Ok(rhs) if array_equal(&items, &rhs))
+        } else {
+            matches!(rhs.as_array(), Ok(rhs) if array_equal(&items, rhs))
+        }
+    }
+
+    fn hash(&self, state: &mut dyn Hasher) {
+        // Same hash as lists and vectors holding the same items
+        if let Ok(items) = self.realize_all() {
+            array_hash(&items, state);
+        }
+    }
+
+    fn uncons(&self) -> Result<Cons, MalError> {
+        self.realize()
+    }
+}
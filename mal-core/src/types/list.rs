@@ -1,34 +1,61 @@
 use std::{
     any::Any,
+    borrow::Cow,
     fmt::{Debug, Display},
+    hash::Hasher,
     iter::FromIterator,
     ops::{Deref, Index},
     rc::Rc,
 };
 
-use super::{array_equal, MalSymbol, MalType};
+use im_rc::Vector;
 
+use crate::{reader::Location, MalError};
+
+use super::{array_equal, array_hash, array_uncons, Cons, MalLazySeq, MalSymbol, MalType};
+
+#[derive(Default, Clone)]
 pub struct MalList {
-    value: Vec<Rc<dyn MalType>>,
+    value: Vector<Rc<dyn MalType>>,
+    location: Option<Location>,
+    meta: Option<Rc<dyn MalType>>,
 }
 
 impl From<Vec<Rc<dyn MalType>>> for MalList {
     fn from(value: Vec<Rc<dyn MalType>>) -> Self {
-        MalList { value }
+        value.into_iter().collect()
+    }
+}
+
+impl From<Vector<Rc<dyn MalType>>> for MalList {
+    fn from(value: Vector<Rc<dyn MalType>>) -> Self {
+        MalList {
+            value,
+            location: None,
+            meta: None,
+        }
     }
 }
 
 impl FromIterator<Rc<dyn MalType>> for MalList {
     fn from_iter<T: IntoIterator<Item = Rc<dyn MalType>>>(iter: T) -> Self {
         let value = iter.into_iter().collect();
-        Self { value }
+        Self {
+            value,
+            location: None,
+            meta: None,
+        }
     }
 }
 
 impl<'a> FromIterator<&'a Rc<dyn MalType>> for MalList {
     fn from_iter<T: IntoIterator<Item = &'a Rc<dyn MalType>>>(iter: T) -> Self {
         let value = iter.into_iter().cloned().collect();
-        Self { value }
+        Self {
+            value,
+            location: None,
+            meta: None,
+        }
     }
 }
 
@@ -70,17 +97,32 @@ impl Index<usize> for MalList {
     }
 }
 
-impl Default for MalList {
-    fn default() -> Self {
-        Self { value: Vec::new() }
-    }
-}
-
 impl MalList {
     pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    pub fn meta(&self) -> Option<&Rc<dyn MalType>> {
+        self.meta.as_ref()
+    }
+
+    pub fn with_meta(&self, meta: Option<Rc<dyn MalType>>) -> Self {
+        Self {
+            value: self.value.clone(),
+            location: self.location.clone(),
+            meta,
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.value.is_empty()
     }
@@ -89,14 +131,24 @@ impl MalList {
         self.value.len()
     }
 
-    pub fn values(&self) -> &[Rc<dyn MalType>] {
-        self.value.as_slice()
+    pub fn values(&self) -> &Vector<Rc<dyn MalType>> {
+        &self.value
     }
 
     pub fn get(&self, idx: usize) -> Option<&Rc<dyn MalType>> {
         self.value.get(idx)
     }
 
+    /// Items after the first, borrowed when the list is stored in a single chunk.
+    pub fn rest(&self) -> Cow<'_, [Rc<dyn MalType>]> {
+        let mut leaves = self.value.leaves();
+        match (leaves.next(), leaves.next()) {
+            (None, _) => Cow::Borrowed(&[]),
+            (Some(chunk), None) => Cow::Borrowed(&chunk[1..]),
+            _ => Cow::Owned(self.value.iter().skip(1).cloned().collect()),
+        }
+    }
+
     pub fn is_special(&self, name: &'static str) -> bool {
         if self.is_empty() {
             return false;
@@ -110,6 +162,10 @@ impl MalList {
 }
 
 impl MalType for MalList {
+    fn type_name() -> &'static str {
+        "list"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -121,14 +177,23 @@ impl MalType for MalList {
     fn equal(&self, rhs: &dyn MalType) -> bool {
         let rhs = match rhs.as_array() {
             Ok(rhs) => rhs,
-            Err(_) => return false,
+            // Lazy sequences compare their realized items
+            Err(_) => return rhs.is::<MalLazySeq>() && rhs.equal(self),
         };
         array_equal(self, rhs)
     }
+
+    fn hash(&self, state: &mut dyn Hasher) {
+        array_hash(self, state);
+    }
+
+    fn uncons(&self) -> Result<Cons, MalError> {
+        Ok(array_uncons(self))
+    }
 }
 
 impl Deref for MalList {
-    type Target = [Rc<dyn MalType>];
+    type Target = Vector<Rc<dyn MalType>>;
 
     fn deref(&self) -> &Self::Target {
         &self.value
@@ -1,33 +1,72 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
     rc::Rc,
 };
 
+use im_rc::Vector;
+
 pub mod atom;
+pub mod bigint;
 pub mod boolean;
+pub mod character;
 pub mod clojure;
 pub mod exception;
+pub mod float;
 pub mod func;
 pub mod hashmap;
 pub mod int;
 pub mod keyword;
+pub mod lazy;
 pub mod list;
+pub mod number;
+pub mod regex;
+pub mod set;
 pub mod string;
 pub mod symbol;
 pub mod vec;
 
 pub use crate::types::{
-    atom::MalAtom, boolean::MalBool, clojure::MalClojure, exception::MalException, func::MalFunc,
-    hashmap::MalHashMap, int::MalInt, keyword::MalKeyword, list::MalList, string::MalString,
-    symbol::MalSymbol, vec::MalVec,
+    atom::MalAtom,
+    bigint::MalBigInt,
+    boolean::MalBool,
+    character::MalChar,
+    clojure::MalClojure,
+    exception::MalException,
+    float::MalFloat,
+    func::MalFunc,
+    hashmap::MalHashMap,
+    int::MalInt,
+    keyword::MalKeyword,
+    lazy::MalLazySeq,
+    list::MalList,
+    number::Number,
+    regex::{MalMatcher, MalRegex},
+    set::MalSet,
+    string::MalString,
+    symbol::MalSymbol,
+    vec::MalVec,
 };
 use crate::MalError;
 
+/// First item and rest of a sequence, `None` when the sequence is empty.
+pub type Cons = Option<(Rc<dyn MalType>, Rc<dyn MalType>)>;
+
 pub trait MalType: Display + Debug + Any {
+    /// Name of the type used in error messages.
+    fn type_name() -> &'static str
+    where
+        Self: Sized;
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn equal(&self, rhs: &dyn MalType) -> bool;
+    /// Values that are `equal` should have the same hash.
+    fn hash(&self, state: &mut dyn Hasher);
+    /// Seq protocol, collections are split into their first item and the rest of the items.
+    fn uncons(&self) -> Result<Cons, MalError> {
+        Err(MalError::WrongType("sequence"))
+    }
 }
 
 impl PartialEq for dyn MalType {
@@ -36,17 +75,25 @@ impl PartialEq for dyn MalType {
     }
 }
 
+impl Eq for dyn MalType {}
+
+impl Hash for dyn MalType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        MalType::hash(self, state)
+    }
+}
+
 impl dyn MalType {
-    pub fn as_type<T: 'static>(&self) -> Result<&T, MalError> {
+    pub fn as_type<T: MalType>(&self) -> Result<&T, MalError> {
         match self.as_any().downcast_ref::<T>() {
             Some(inner) => Ok(inner),
-            None => Err(MalError::TypeError),
+            None => Err(MalError::WrongType(T::type_name())),
         }
     }
-    pub fn as_type_mut<T: 'static>(&mut self) -> Result<&mut T, MalError> {
+    pub fn as_type_mut<T: MalType>(&mut self) -> Result<&mut T, MalError> {
         match self.as_any_mut().downcast_mut::<T>() {
             Some(inner) => Ok(inner),
-            None => Err(MalError::TypeError),
+            None => Err(MalError::WrongType(T::type_name())),
         }
     }
 
@@ -54,13 +101,13 @@ impl dyn MalType {
         self.as_any().is::<T>()
     }
 
-    pub fn as_array(&self) -> Result<&[Rc<dyn MalType>], MalError> {
+    pub fn as_array(&self) -> Result<&Vector<Rc<dyn MalType>>, MalError> {
         if let Ok(list) = self.as_type::<MalList>() {
             Ok(list)
         } else if let Ok(vector) = self.as_type::<MalVec>() {
             Ok(vector)
         } else {
-            Err(MalError::TypeError)
+            Err(MalError::WrongType("list or vector"))
         }
     }
 
@@ -83,7 +130,7 @@ impl dyn MalType {
     }
 }
 
-pub fn array_equal(lhs: &[Rc<dyn MalType>], rhs: &[Rc<dyn MalType>]) -> bool {
+pub fn array_equal(lhs: &Vector<Rc<dyn MalType>>, rhs: &Vector<Rc<dyn MalType>>) -> bool {
     if lhs.len() != rhs.len() {
         return false;
     }
@@ -95,6 +142,20 @@ pub fn array_equal(lhs: &[Rc<dyn MalType>], rhs: &[Rc<dyn MalType>]) -> bool {
     true
 }
 
+/// First item and rest of a list or vector, the rest is always a list.
+pub fn array_uncons(arr: &Vector<Rc<dyn MalType>>) -> Cons {
+    let first = arr.front()?.clone();
+    let rest: Rc<dyn MalType> = Rc::from(MalList::from(arr.skip(1)));
+    Some((first, rest))
+}
+
+pub fn array_hash(arr: &Vector<Rc<dyn MalType>>, mut state: &mut dyn Hasher) {
+    arr.len().hash(&mut state);
+    for item in arr {
+        MalType::hash(item.as_ref(), state);
+    }
+}
+
 pub struct MalNil {}
 
 impl Debug for MalNil {
@@ -110,6 +171,10 @@ impl Display for MalNil {
 }
 
 impl MalType for MalNil {
+    fn type_name() -> &'static str {
+        "nil"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -121,6 +186,12 @@ impl MalType for MalNil {
     fn equal(&self, rhs: &dyn MalType) -> bool {
         rhs.is::<MalNil>()
     }
+
+    fn hash(&self, _state: &mut dyn Hasher) {}
+
+    fn uncons(&self) -> Result<Cons, MalError> {
+        Ok(None)
+    }
 }
 
 impl MalNil {
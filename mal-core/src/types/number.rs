@@ -0,0 +1,163 @@
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    ops::{Add, Div, Mul, Sub},
+    rc::Rc,
+};
+
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::MalError;
+
+use super::{MalBigInt, MalFloat, MalInt, MalType};
+
+/// Numeric value extracted from `MalInt`, `MalBigInt` or `MalFloat`.
+///
+/// Arithmetic and comparison between numbers of different kinds promote
+/// integers to floating point values, integer operations that overflow `i64`
+/// promote to big integers.
+#[derive(Debug, Clone)]
+pub enum Number {
+    Int(i64),
+    BigInt(BigInt),
+    Float(f64),
+}
+
+enum Promoted {
+    Int(i64, i64),
+    BigInt(BigInt, BigInt),
+    Float(f64, f64),
+}
+
+impl Number {
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Number::Int(int) => *int as f64,
+            Number::BigInt(bigint) => bigint.to_f64().unwrap_or(f64::NAN),
+            Number::Float(float) => *float,
+        }
+    }
+
+    fn into_bigint(self) -> BigInt {
+        match self {
+            Number::Int(int) => BigInt::from(int),
+            Number::BigInt(bigint) => bigint,
+            Number::Float(_) => unreachable!("floats aren't promoted to big integers"),
+        }
+    }
+
+    fn promote(lhs: Number, rhs: Number) -> Promoted {
+        match (lhs, rhs) {
+            (Number::Int(lhs), Number::Int(rhs)) => Promoted::Int(lhs, rhs),
+            (lhs @ Number::Float(_), rhs) | (lhs, rhs @ Number::Float(_)) => {
+                Promoted::Float(lhs.as_f64(), rhs.as_f64())
+            }
+            (lhs, rhs) => Promoted::BigInt(lhs.into_bigint(), rhs.into_bigint()),
+        }
+    }
+}
+
+impl TryFrom<&dyn MalType> for Number {
+    type Error = MalError;
+
+    fn try_from(value: &dyn MalType) -> Result<Self, Self::Error> {
+        if let Ok(int) = value.as_type::<MalInt>() {
+            Ok(Number::Int(int.value()))
+        } else if let Ok(bigint) = value.as_type::<MalBigInt>() {
+            Ok(Number::BigInt(bigint.value().clone()))
+        } else if let Ok(float) = value.as_type::<MalFloat>() {
+            Ok(Number::Float(float.value()))
+        } else {
+            Err(MalError::WrongType("number"))
+        }
+    }
+}
+
+impl From<Number> for Rc<dyn MalType> {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Int(int) => Rc::from(MalInt::from(int)),
+            Number::BigInt(bigint) => Rc::from(MalBigInt::from(bigint)),
+            Number::Float(float) => Rc::from(MalFloat::from(float)),
+        }
+    }
+}
+
+impl Add for Number {
+    type Output = Number;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        match Number::promote(self, rhs) {
+            Promoted::Int(lhs, rhs) => match lhs.checked_add(rhs) {
+                Some(result) => Number::Int(result),
+                None => Number::BigInt(BigInt::from(lhs) + rhs),
+            },
+            Promoted::BigInt(lhs, rhs) => Number::BigInt(lhs + rhs),
+            Promoted::Float(lhs, rhs) => Number::Float(lhs + rhs),
+        }
+    }
+}
+
+impl Sub for Number {
+    type Output = Number;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        match Number::promote(self, rhs) {
+            Promoted::Int(lhs, rhs) => match lhs.checked_sub(rhs) {
+                Some(result) => Number::Int(result),
+                None => Number::BigInt(BigInt::from(lhs) - rhs),
+            },
+            Promoted::BigInt(lhs, rhs) => Number::BigInt(lhs - rhs),
+            Promoted::Float(lhs, rhs) => Number::Float(lhs - rhs),
+        }
+    }
+}
+
+impl Mul for Number {
+    type Output = Number;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        match Number::promote(self, rhs) {
+            Promoted::Int(lhs, rhs) => match lhs.checked_mul(rhs) {
+                Some(result) => Number::Int(result),
+                None => Number::BigInt(BigInt::from(lhs) * rhs),
+            },
+            Promoted::BigInt(lhs, rhs) => Number::BigInt(lhs * rhs),
+            Promoted::Float(lhs, rhs) => Number::Float(lhs * rhs),
+        }
+    }
+}
+
+impl Div for Number {
+    type Output = Result<Number, MalError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        match Number::promote(self, rhs) {
+            Promoted::Int(_, 0) => Err(MalError::DivisionByZero),
+            Promoted::Int(lhs, rhs) => match lhs.checked_div(rhs) {
+                Some(result) => Ok(Number::Int(result)),
+                None => Ok(Number::BigInt(BigInt::from(lhs) / rhs)),
+            },
+            Promoted::BigInt(_, rhs) if rhs.is_zero() => Err(MalError::DivisionByZero),
+            Promoted::BigInt(lhs, rhs) => Ok(Number::BigInt(lhs / rhs)),
+            Promoted::Float(lhs, rhs) => Ok(Number::Float(lhs / rhs)),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match Number::promote(self.clone(), other.clone()) {
+            Promoted::Int(lhs, rhs) => lhs.partial_cmp(&rhs),
+            Promoted::BigInt(lhs, rhs) => lhs.partial_cmp(&rhs),
+            Promoted::Float(lhs, rhs) => lhs.partial_cmp(&rhs),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    any::Any,
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Range,
+    ptr,
+    rc::Rc,
+};
+
+use regex::Regex;
+
+use super::{MalNil, MalString, MalType, MalVec};
+
+/// Cache is cleared when it grows past this many patterns.
+const CACHE_LIMIT: usize = 256;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<String, Rc<MalRegex>>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Clone)]
+pub struct MalRegex {
+    value: Regex,
+    /// Pattern that has to match the whole string, built on the first `re-matches`.
+    anchored: OnceCell<Regex>,
+}
+
+impl From<Regex> for MalRegex {
+    fn from(value: Regex) -> Self {
+        Self {
+            value,
+            anchored: OnceCell::new(),
+        }
+    }
+}
+
+impl Debug for MalRegex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Unescaped '"' would end the literal when read back, escaping keeps the pattern the same
+        write!(f, "#\"")?;
+        let mut escaped = false;
+        for ch in self.value.as_str().chars() {
+            if ch == '"' && !escaped {
+                write!(f, "\\")?;
+            }
+            escaped = ch == '\\' && !escaped;
+            write!(f, "{}", ch)?;
+        }
+        write!(f, "\"")
+    }
+}
+
+impl Display for MalRegex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value.as_str())
+    }
+}
+
+impl MalRegex {
+    /// Compiles the pattern, patterns that were already compiled are reused from a cache.
+    pub fn compile(pattern: &str) -> Result<Rc<Self>, regex::Error> {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if let Some(regex) = cache.get(pattern) {
+                return Ok(regex.clone());
+            }
+            if cache.len() >= CACHE_LIMIT {
+                cache.clear();
+            }
+            let regex = Rc::new(Self::from(Regex::new(pattern)?));
+            cache.insert(pattern.to_string(), regex.clone());
+            Ok(regex)
+        })
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.value
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.value.as_str()
+    }
+
+    /// First match at or after byte offset `start`. Matches of patterns with groups are vectors
+    /// of the whole match followed by the groups, with `nil` for groups that didn't take part.
+    pub fn find_at(&self, text: &str, start: usize) -> Option<(Rc<dyn MalType>, Range<usize>)> {
+        find_at(&self.value, text, start)
+    }
+
+    /// Match of the whole of `text`, in the same shape as [`MalRegex::find_at`].
+    pub fn matches(&self, text: &str) -> Result<Option<Rc<dyn MalType>>, regex::Error> {
+        let anchored = match self.anchored.get() {
+            Some(anchored) => anchored,
+            None => {
+                // Anchoring the pattern makes alternatives that match a shorter prefix fail
+                let anchored = Regex::new(&format!("^(?:{})$", self.as_str()))?;
+                self.anchored.get_or_init(|| anchored)
+            }
+        };
+        Ok(find_at(anchored, text, 0).map(|(value, _)| value))
+    }
+
+    /// Every non-overlapping match in `text`.
+    pub fn find_all(&self, text: &str) -> Vec<(Rc<dyn MalType>, Range<usize>)> {
+        let mut result = Vec::new();
+        let mut start = Some(0);
+        while let Some(found) = start.and_then(|start| self.find_at(text, start)) {
+            start = next_start(text, &found.1);
+            result.push(found);
+        }
+        result
+    }
+}
+
+fn find_at(regex: &Regex, text: &str, start: usize) -> Option<(Rc<dyn MalType>, Range<usize>)> {
+    let mut locations = regex.capture_locations();
+    let found = regex.captures_read_at(&mut locations, text, start)?;
+    let value: Rc<dyn MalType> = if locations.len() == 1 {
+        Rc::from(MalString::from(found.as_str()))
+    } else {
+        let groups: MalVec = (0..locations.len())
+            .map(|index| match locations.get(index) {
+                Some((start, end)) => {
+                    Rc::from(MalString::from(&text[start..end])) as Rc<dyn MalType>
+                }
+                None => MalNil::new(),
+            })
+            .collect();
+        Rc::from(groups)
+    };
+    Some((value, found.range()))
+}
+
+/// Where to continue searching after a match, empty matches skip a character so the search
+/// doesn't get stuck.
+fn next_start(text: &str, range: &Range<usize>) -> Option<usize> {
+    if !range.is_empty() {
+        Some(range.end)
+    } else {
+        text[range.end..]
+            .chars()
+            .next()
+            .map(|ch| range.end + ch.len_utf8())
+    }
+}
+
+impl MalType for MalRegex {
+    fn type_name() -> &'static str {
+        "regex"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    /// Patterns are equal when their source is, the cache may hand out different instances.
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        match rhs.as_type::<Self>() {
+            Ok(rhs) => self.as_str() == rhs.as_str(),
+            Err(_) => false,
+        }
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.as_str().hash(&mut state);
+    }
+}
+
+/// Stateful search through a string, each `re-find` continues after the previous match.
+pub struct MalMatcher {
+    regex: MalRegex,
+    text: String,
+    start: RefCell<Option<usize>>,
+    last: RefCell<Option<Rc<dyn MalType>>>,
+}
+
+impl Debug for MalMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#<matcher {:?}>", self.regex)
+    }
+}
+
+impl Display for MalMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#<matcher {:?}>", self.regex)
+    }
+}
+
+impl MalMatcher {
+    pub fn new(regex: MalRegex, text: String) -> Self {
+        Self {
+            regex,
+            text,
+            start: RefCell::new(Some(0)),
+            last: RefCell::new(None),
+        }
+    }
+
+    /// Next match, or `None` once the string is exhausted.
+    pub fn find(&self) -> Option<Rc<dyn MalType>> {
+        let start = *self.start.borrow();
+        let found = start.and_then(|start| self.regex.find_at(&self.text, start));
+        let value = match found {
+            Some((value, range)) => {
+                *self.start.borrow_mut() = next_start(&self.text, &range);
+                Some(value)
+            }
+            None => {
+                *self.start.borrow_mut() = None;
+                None
+            }
+        };
+        *self.last.borrow_mut() = value.clone();
+        value
+    }
+
+    /// Result of the last successful `find`.
+    pub fn groups(&self) -> Option<Rc<dyn MalType>> {
+        self.last.borrow().clone()
+    }
+}
+
+impl MalType for MalMatcher {
+    fn type_name() -> &'static str {
+        "matcher"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        match rhs.as_type::<Self>() {
+            Ok(rhs) => ptr::eq(self, rhs),
+            Err(_) => false,
+        }
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        ptr::hash(self, &mut state);
+    }
+}
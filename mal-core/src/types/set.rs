@@ -0,0 +1,167 @@
+use std::{
+    any::Any,
+    collections::hash_map::DefaultHasher,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    rc::Rc,
+};
+
+use im_rc::{hashset::Iter, HashSet};
+
+use crate::MalError;
+
+use super::{array_uncons, Cons, MalType};
+
+#[derive(Default, Clone)]
+pub struct MalSet {
+    value: HashSet<Rc<dyn MalType>>,
+    meta: Option<Rc<dyn MalType>>,
+}
+
+impl FromIterator<Rc<dyn MalType>> for MalSet {
+    fn from_iter<T: IntoIterator<Item = Rc<dyn MalType>>>(iter: T) -> Self {
+        let mut result = Self::new();
+        result.insert_mut(iter);
+        result
+    }
+}
+
+impl<'a> FromIterator<&'a Rc<dyn MalType>> for MalSet {
+    fn from_iter<T: IntoIterator<Item = &'a Rc<dyn MalType>>>(iter: T) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
+
+impl Debug for MalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{{")?;
+        let mut iter = self.value.iter();
+        match iter.next() {
+            Some(item) => write!(f, "{:?}", item)?,
+            None => return write!(f, "}}"),
+        }
+        for item in iter {
+            write!(f, " {:?}", item)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Display for MalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{{")?;
+        let mut iter = self.value.iter();
+        match iter.next() {
+            Some(item) => write!(f, "{}", item)?,
+            None => return write!(f, "}}"),
+        }
+        for item in iter {
+            write!(f, " {}", item)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl MalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn meta(&self) -> Option<&Rc<dyn MalType>> {
+        self.meta.as_ref()
+    }
+
+    pub fn with_meta(&self, meta: Option<Rc<dyn MalType>>) -> Self {
+        Self {
+            value: self.value.clone(),
+            meta,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    pub fn iter(&self) -> Iter<'_, Rc<dyn MalType>> {
+        self.value.iter()
+    }
+
+    fn insert_mut<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = Rc<dyn MalType>>,
+    {
+        for item in iter {
+            // An equal item that is already present is kept
+            if !self.value.contains(item.as_ref()) {
+                self.value.insert(item);
+            }
+        }
+    }
+
+    pub fn insert<T>(&self, iter: T) -> Self
+    where
+        T: IntoIterator<Item = Rc<dyn MalType>>,
+    {
+        let mut result = self.clone();
+        result.insert_mut(iter);
+        result
+    }
+
+    pub fn remove<'a, T>(&self, iter: T) -> Self
+    where
+        T: IntoIterator<Item = &'a Rc<dyn MalType>>,
+    {
+        let mut result = self.clone();
+        for item in iter {
+            result.value.remove(item.as_ref());
+        }
+        result
+    }
+
+    pub fn contains(&self, item: &dyn MalType) -> bool {
+        self.value.contains(item)
+    }
+}
+
+impl MalType for MalSet {
+    fn type_name() -> &'static str {
+        "set"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn equal(&self, rhs: &dyn MalType) -> bool {
+        let rhs = match rhs.as_type::<Self>() {
+            Ok(rhs) => rhs,
+            Err(_) => return false,
+        };
+        self.len() == rhs.len() && self.iter().all(|item| rhs.contains(item.as_ref()))
+    }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        // Same as for hash-maps, the hash doesn't depend on the order of iteration
+        let mut combined: u64 = 0;
+        for item in self.iter() {
+            let mut hasher = DefaultHasher::new();
+            MalType::hash(item.as_ref(), &mut hasher);
+            combined = combined.wrapping_add(hasher.finish());
+        }
+        self.len().hash(&mut state);
+        combined.hash(&mut state);
+    }
+
+    fn uncons(&self) -> Result<Cons, MalError> {
+        Ok(array_uncons(&self.iter().cloned().collect()))
+    }
+}
@@ -2,9 +2,14 @@ use core::fmt;
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    rc::Rc,
+    str::Chars,
 };
 
-use super::MalType;
+use crate::MalError;
+
+use super::{Cons, MalChar, MalList, MalType};
 
 pub struct MalString {
     pub value: String,
@@ -29,25 +34,15 @@ impl Debug for MalString {
 
 impl Display for MalString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\"")?;
-        let mut chars = self.value.chars().peekable();
-        while let Some(ch) = chars.next() {
-            match ch {
-                '\\' => match chars.next() {
-                    Some('"') => write!(f, "\"")?,
-                    Some('n') => write!(f, "\n")?,
-                    Some('t') => write!(f, "\t")?,
-                    Some('r') => write!(f, "\r")?,
-                    Some(_) | None => return fmt::Result::Err(fmt::Error),
-                },
-                _ => write!(f, "{}", ch)?,
-            }
-        }
-        write!(f, "\"")
+        write!(f, "{}", self.value)
     }
 }
 
 impl MalType for MalString {
+    fn type_name() -> &'static str {
+        "string"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -62,10 +57,30 @@ impl MalType for MalString {
             Err(_) => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.value.hash(&mut state);
+    }
+
+    fn uncons(&self) -> Result<Cons, MalError> {
+        let mut chars = self.chars();
+        let first = match chars.next() {
+            Some(first) => Rc::from(MalChar::from(first)),
+            None => return Ok(None),
+        };
+        let rest: MalList = chars
+            .map(|ch| Rc::from(MalChar::from(ch)) as Rc<dyn MalType>)
+            .collect();
+        Ok(Some((first, Rc::from(rest))))
+    }
 }
 
 impl MalString {
     pub fn as_str(&self) -> &str {
         self.value.as_str()
     }
+
+    pub fn chars(&self) -> Chars<'_> {
+        self.value.chars()
+    }
 }
@@ -1,6 +1,7 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
 };
 
 use super::MalType;
@@ -22,6 +23,10 @@ where
 }
 
 impl MalSymbol {
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
     pub fn starts_with(&self, start: &str) -> bool {
         self.value.starts_with(start)
     }
@@ -39,6 +44,10 @@ impl Display for MalSymbol {
 }
 
 impl MalType for MalSymbol {
+    fn type_name() -> &'static str {
+        "symbol"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -53,6 +62,10 @@ impl MalType for MalSymbol {
             Err(_) => false,
         }
     }
+
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        self.value.hash(&mut state);
+    }
 }
 
 impl PartialEq<&str> for &MalSymbol {
@@ -1,34 +1,47 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    hash::Hasher,
     iter::FromIterator,
     ops::Deref,
     rc::Rc,
 };
 
-use super::{array_equal, MalType};
+use im_rc::{vector::ConsumingIter, Vector};
 
+use crate::MalError;
+
+use super::{array_equal, array_hash, array_uncons, Cons, MalLazySeq, MalType};
+
+#[derive(Clone)]
 pub struct MalVec {
-    value: Vec<Rc<dyn MalType>>,
+    value: Vector<Rc<dyn MalType>>,
+    meta: Option<Rc<dyn MalType>>,
 }
 
 impl From<Vec<Rc<dyn MalType>>> for MalVec {
     fn from(value: Vec<Rc<dyn MalType>>) -> Self {
-        MalVec { value }
+        value.into_iter().collect()
+    }
+}
+
+impl From<Vector<Rc<dyn MalType>>> for MalVec {
+    fn from(value: Vector<Rc<dyn MalType>>) -> Self {
+        MalVec { value, meta: None }
     }
 }
 
 impl FromIterator<Rc<dyn MalType>> for MalVec {
     fn from_iter<T: IntoIterator<Item = Rc<dyn MalType>>>(iter: T) -> Self {
         let value = iter.into_iter().collect();
-        Self { value }
+        Self { value, meta: None }
     }
 }
 
 impl<'a> FromIterator<&'a Rc<dyn MalType>> for MalVec {
     fn from_iter<T: IntoIterator<Item = &'a Rc<dyn MalType>>>(iter: T) -> Self {
         let value = iter.into_iter().cloned().collect();
-        Self { value }
+        Self { value, meta: None }
     }
 }
 
@@ -71,12 +84,27 @@ impl MalVec {
         self.value.len()
     }
 
-    pub fn values(&self) -> &[Rc<dyn MalType>] {
-        self.value.as_slice()
+    pub fn values(&self) -> &Vector<Rc<dyn MalType>> {
+        &self.value
+    }
+
+    pub fn meta(&self) -> Option<&Rc<dyn MalType>> {
+        self.meta.as_ref()
+    }
+
+    pub fn with_meta(&self, meta: Option<Rc<dyn MalType>>) -> Self {
+        Self {
+            value: self.value.clone(),
+            meta,
+        }
     }
 }
 
 impl MalType for MalVec {
+    fn type_name() -> &'static str {
+        "vector"
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -88,16 +116,25 @@ impl MalType for MalVec {
     fn equal(&self, rhs: &dyn MalType) -> bool {
         let rhs = match rhs.as_array() {
             Ok(rhs) => rhs,
-            Err(_) => return false,
+            // Lazy sequences compare their realized items
+            Err(_) => return rhs.is::<MalLazySeq>() && rhs.equal(self),
         };
-        array_equal(&self, rhs)
+        array_equal(self, rhs)
+    }
+
+    fn hash(&self, state: &mut dyn Hasher) {
+        array_hash(self, state);
+    }
+
+    fn uncons(&self) -> Result<Cons, MalError> {
+        Ok(array_uncons(self))
     }
 }
 
 impl IntoIterator for MalVec {
     type Item = Rc<dyn MalType>;
 
-    type IntoIter = std::vec::IntoIter<Rc<dyn MalType>>;
+    type IntoIter = ConsumingIter<Rc<dyn MalType>>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.value.into_iter()
@@ -105,7 +142,7 @@ impl IntoIterator for MalVec {
 }
 
 impl Deref for MalVec {
-    type Target = [Rc<dyn MalType>];
+    type Target = Vector<Rc<dyn MalType>>;
 
     fn deref(&self) -> &Self::Target {
         &self.value
@@ -0,0 +1,567 @@
+//! Conversions between MAL values and Rust types implementing `Serialize` and `Deserialize`.
+//!
+//! Structs become hash-maps with keyword keys, sequences become vectors, `None` and `()` become
+//! nil. Unit enum variants are keywords and other variants are single entry hash-maps such as
+//! `{:circle 1.5}`. Reading values back, keywords and symbols are accepted wherever a string is
+//! expected and lists, sets and lazy sequences wherever a sequence is expected.
+
+use std::{convert::TryFrom, fmt::Display, rc::Rc};
+
+use num_traits::ToPrimitive;
+use serde::{
+    de::{self, DeserializeOwned, IntoDeserializer, Visitor},
+    forward_to_deserialize_any, ser, Serialize,
+};
+
+use crate::{
+    seq,
+    types::{
+        MalBigInt, MalBool, MalChar, MalFloat, MalHashMap, MalInt, MalKeyword, MalNil, MalString,
+        MalSymbol, MalType, MalVec,
+    },
+    MalError, MalResult,
+};
+
+impl ser::Error for MalError {
+    fn custom<T: Display>(msg: T) -> Self {
+        MalError::Conversion(msg.to_string())
+    }
+}
+
+impl de::Error for MalError {
+    fn custom<T: Display>(msg: T) -> Self {
+        MalError::Conversion(msg.to_string())
+    }
+}
+
+/// MAL value holding the data of `value`.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> MalResult {
+    value.serialize(Serializer)
+}
+
+/// Rust value read from the MAL value `value`.
+pub fn from_value<T: DeserializeOwned>(value: &Rc<dyn MalType>) -> Result<T, MalError> {
+    T::deserialize(Deserializer::from(value.clone()))
+}
+
+fn keyword(name: &str) -> Rc<dyn MalType> {
+    Rc::from(MalKeyword::from(format!(":{}", name)))
+}
+
+/// `{:variant value}`, the representation of enum variants holding data.
+fn variant(name: &str, value: Rc<dyn MalType>) -> MalResult {
+    Ok(Rc::from(MalHashMap::try_from(vec![keyword(name), value])?))
+}
+
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVec;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeMap;
+
+    fn serialize_bool(self, v: bool) -> MalResult {
+        Ok(Rc::from(MalBool::from(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> MalResult {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> MalResult {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> MalResult {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> MalResult {
+        Ok(Rc::from(MalInt::from(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> MalResult {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> MalResult {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> MalResult {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> MalResult {
+        // Integers that don't fit into `i64` are big integers, as they are when read
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => Ok(Rc::from(MalBigInt::from(v))),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> MalResult {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> MalResult {
+        Ok(Rc::from(MalFloat::from(v)))
+    }
+
+    fn serialize_char(self, v: char) -> MalResult {
+        Ok(Rc::from(MalChar::from(v)))
+    }
+
+    fn serialize_str(self, v: &str) -> MalResult {
+        Ok(Rc::from(MalString::from(v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> MalResult {
+        let bytes: MalVec = v
+            .iter()
+            .map(|byte| Rc::from(MalInt::from(i64::from(*byte))) as Rc<dyn MalType>)
+            .collect();
+        Ok(Rc::from(bytes))
+    }
+
+    fn serialize_none(self) -> MalResult {
+        Ok(MalNil::new())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> MalResult {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> MalResult {
+        Ok(MalNil::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> MalResult {
+        Ok(MalNil::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> MalResult {
+        Ok(keyword(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> MalResult {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> MalResult {
+        variant(name, value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, MalError> {
+        Ok(SerializeVec::new(len.unwrap_or(0), None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, MalError> {
+        Ok(SerializeVec::new(len, None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, MalError> {
+        Ok(SerializeVec::new(len, None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, MalError> {
+        Ok(SerializeVec::new(len, Some(variant)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, MalError> {
+        Ok(SerializeMap::new(len.unwrap_or(0), None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, MalError> {
+        Ok(SerializeMap::new(len, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeMap, MalError> {
+        Ok(SerializeMap::new(len, Some(variant)))
+    }
+}
+
+/// Items of a vector, wrapped in `{:variant [items...]}` for tuple variants.
+struct SerializeVec {
+    items: Vec<Rc<dyn MalType>>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeVec {
+    fn new(len: usize, variant: Option<&'static str>) -> Self {
+        Self {
+            items: Vec::with_capacity(len),
+            variant,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), MalError> {
+        self.items.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> MalResult {
+        let items = Rc::from(MalVec::from(self.items));
+        match self.variant {
+            Some(name) => variant(name, items),
+            None => Ok(items),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), MalError> {
+        self.push(value)
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), MalError> {
+        self.push(value)
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), MalError> {
+        self.push(value)
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVec {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), MalError> {
+        self.push(value)
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+/// Keys and values of a hash-map, wrapped in `{:variant {...}}` for struct variants.
+struct SerializeMap {
+    entries: Vec<Rc<dyn MalType>>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeMap {
+    fn new(len: usize, variant: Option<&'static str>) -> Self {
+        Self {
+            entries: Vec::with_capacity(2 * len),
+            variant,
+        }
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), MalError> {
+        self.entries.push(keyword(key));
+        self.entries.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> MalResult {
+        let map = Rc::from(MalHashMap::try_from(self.entries)?);
+        match self.variant {
+            Some(name) => variant(name, map),
+            None => Ok(map),
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), MalError> {
+        self.entries.push(key.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), MalError> {
+        self.entries.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), MalError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeMap {
+    type Ok = Rc<dyn MalType>;
+    type Error = MalError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), MalError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> MalResult {
+        self.finish()
+    }
+}
+
+struct Deserializer {
+    value: Rc<dyn MalType>,
+}
+
+impl From<Rc<dyn MalType>> for Deserializer {
+    fn from(value: Rc<dyn MalType>) -> Self {
+        Self { value }
+    }
+}
+
+impl Deserializer {
+    /// Text of strings, keywords without the leading `:` and symbols.
+    fn as_str(&self) -> Option<&str> {
+        if let Ok(string) = self.value.as_type::<MalString>() {
+            Some(string.as_str())
+        } else if let Ok(keyword) = self.value.as_type::<MalKeyword>() {
+            Some(keyword.value.strip_prefix(':').unwrap_or(&keyword.value))
+        } else if let Ok(symbol) = self.value.as_type::<MalSymbol>() {
+            Some(symbol.as_str())
+        } else {
+            None
+        }
+    }
+
+    fn invalid(&self, expected: &str) -> MalError {
+        MalError::Conversion(format!("expected {}, got {:?}", expected, self.value))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = MalError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MalError> {
+        let value = &self.value;
+        if value.is::<MalNil>() {
+            visitor.visit_unit()
+        } else if let Ok(boolean) = value.as_type::<MalBool>() {
+            visitor.visit_bool(boolean.value())
+        } else if let Ok(int) = value.as_type::<MalInt>() {
+            visitor.visit_i64(int.value())
+        } else if let Ok(bigint) = value.as_type::<MalBigInt>() {
+            match (bigint.value().to_i64(), bigint.value().to_u64()) {
+                (Some(v), _) => visitor.visit_i64(v),
+                (None, Some(v)) => visitor.visit_u64(v),
+                (None, None) => Err(self.invalid("a 64 bit integer")),
+            }
+        } else if let Ok(float) = value.as_type::<MalFloat>() {
+            visitor.visit_f64(float.value())
+        } else if let Ok(ch) = value.as_type::<MalChar>() {
+            visitor.visit_char(ch.value())
+        } else if let Some(string) = self.as_str() {
+            visitor.visit_str(string)
+        } else if let Ok(map) = value.as_type::<MalHashMap>() {
+            let entries = map.iter().map(|(key, value)| {
+                (
+                    Deserializer::from(key.clone()),
+                    Deserializer::from(value.clone()),
+                )
+            });
+            let mut access = de::value::MapDeserializer::new(entries);
+            let result = visitor.visit_map(&mut access)?;
+            access.end()?;
+            Ok(result)
+        } else {
+            let items = seq::to_vector(value).map_err(|_| self.invalid("a value"))?;
+            let mut access =
+                de::value::SeqDeserializer::new(items.into_iter().map(Deserializer::from));
+            let result = visitor.visit_seq(&mut access)?;
+            access.end()?;
+            Ok(result)
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MalError> {
+        if self.value.is::<MalNil>() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, MalError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MalError> {
+        match self.as_str() {
+            Some(string) => visitor.visit_str(string),
+            None => Err(self.invalid("a string")),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MalError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MalError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MalError> {
+        if let Some(name) = self.as_str() {
+            return visitor.visit_enum(name.to_owned().into_deserializer());
+        }
+        let map = self
+            .value
+            .as_type::<MalHashMap>()
+            .map_err(|_| self.invalid("a keyword or a single entry hash-map"))?;
+        match map.iter().next() {
+            Some((name, value)) if map.len() == 1 => visitor.visit_enum(Variant {
+                name: name.clone(),
+                value: value.clone(),
+            }),
+            _ => Err(self.invalid("a keyword or a single entry hash-map")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, MalError> for Deserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Enum variant read from `{:name value}`.
+struct Variant {
+    name: Rc<dyn MalType>,
+    value: Rc<dyn MalType>,
+}
+
+impl<'de> de::EnumAccess<'de> for Variant {
+    type Error = MalError;
+    type Variant = Deserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Deserializer), MalError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let name = seed.deserialize(Deserializer::from(self.name))?;
+        Ok((name, Deserializer::from(self.value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer {
+    type Error = MalError;
+
+    fn unit_variant(self) -> Result<(), MalError> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, MalError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, MalError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MalError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,138 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+#[test]
+pub fn testing_filter_and_reduce() -> Result<(), MalError> {
+    assert_eq!(eval_print("(filter (fn* [x] (> x 1)) [1 2 3])")?, "(2 3)");
+    assert_eq!(eval_print("(remove (fn* [x] (> x 1)) '(1 2 3))")?, "(1)");
+    assert_eq!(
+        eval_print("(take 2 (filter (fn* [x] (> x 10)) (range)))")?,
+        "(11 12)"
+    );
+    assert_eq!(eval_print("(reduce + [1 2 3])")?, "6");
+    assert_eq!(eval_print("(reduce + 10 [1 2 3])")?, "16");
+    assert_eq!(eval_print("(reduce + [])")?, "0");
+    assert_eq!(
+        eval_print("(reduce (fn* [acc x] (conj acc x)) [] \"ab\")")?,
+        r"[\a \b]"
+    );
+    assert_eq!(
+        eval_print("(some (fn* [x] (if (> x 1) (* 10 x) nil)) [1 2 3])")?,
+        "20"
+    );
+    assert_eq!(eval_print("(some (fn* [x] (> x 5)) [1 2])")?, "nil");
+    assert_eq!(eval_print("(some (fn* [x] (> x 5)) (range))")?, "true");
+    assert_eq!(eval_print("(every? number? [1 2])")?, "true");
+    assert_eq!(eval_print("(every? number? [1 :a])")?, "false");
+    assert_eq!(eval_print("(every? number? [])")?, "true");
+    let err = eval_print("(filter 1 [1])").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`filter`: argument 1 expected function, got 1"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_sort() -> Result<(), MalError> {
+    assert_eq!(eval_print("(sort [3 1.5 2])")?, "(1.5 2 3)");
+    assert_eq!(eval_print(r#"(sort ["b" "c" "a"])"#)?, r#"("a" "b" "c")"#);
+    assert_eq!(eval_print("(sort [:b :a])")?, "(:a :b)");
+    assert_eq!(eval_print("(sort > [3 1 2])")?, "(3 2 1)");
+    assert_eq!(eval_print("(sort (fn* [a b] (- b a)) [3 1 2])")?, "(3 2 1)");
+    assert_eq!(
+        eval_print(r#"(sort-by count ["ccc" "a" "bb"])"#)?,
+        r#"("a" "bb" "ccc")"#
+    );
+    // Sorting is stable
+    assert_eq!(
+        eval_print("(sort-by first [[1 :a] [0 :b] [1 :c] [0 :d]])")?,
+        "([0 :b] [0 :d] [1 :a] [1 :c])"
+    );
+    assert_eq!(
+        eval_print("(sort-by first > [[1 :a] [3 :b] [2 :c]])")?,
+        "([3 :b] [2 :c] [1 :a])"
+    );
+    let err = eval_print(r#"(sort [1 "a"])"#).unwrap_err();
+    assert!(matches!(err.inner(), MalError::NotComparable(..)));
+    assert_eq!(
+        err.inner().to_string(),
+        r#"`1` and `"a"` can't be compared"#
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_grouping() -> Result<(), MalError> {
+    let env = Env::new();
+    rep(r#"(def! groups (group-by count ["a" "bb" "c"]))"#, &env)?;
+    assert_eq!(rep("(get groups 1)", &env)?, r#"["a" "c"]"#);
+    assert_eq!(rep("(get groups 2)", &env)?, r#"["bb"]"#);
+    rep("(def! freqs (frequencies [:a :b :a]))", &env)?;
+    assert_eq!(rep("(get freqs :a)", &env)?, "2");
+    assert_eq!(rep("(count freqs)", &env)?, "2");
+    assert_eq!(rep("(partition 2 [1 2 3 4 5])", &env)?, "((1 2) (3 4))");
+    assert_eq!(rep("(partition 2 1 [1 2 3])", &env)?, "((1 2) (2 3))");
+    assert_eq!(rep("(partition 3 [1 2])", &env)?, "()");
+    assert_eq!(rep("(interleave [1 2 3] [:a :b])", &env)?, "(1 :a 2 :b)");
+    assert_eq!(rep("(interleave (range) \"ab\")", &env)?, r"(0 \a 1 \b)");
+    assert_eq!(rep("(distinct [1 2 1 3 2])", &env)?, "(1 2 3)");
+    assert_eq!(
+        rep("(= (zipmap [:a :b] [1 2 3]) {:a 1 :b 2})", &env)?,
+        "true"
+    );
+    let err = rep("(partition 0 [1])", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::ArgType { .. }));
+    Ok(())
+}
+
+#[test]
+pub fn testing_into() -> Result<(), MalError> {
+    assert_eq!(eval_print("(into [0] '(1 2))")?, "[0 1 2]");
+    assert_eq!(eval_print("(into '(0) [1 2])")?, "(0 1 2)");
+    assert_eq!(eval_print("(into nil [1 2])")?, "(1 2)");
+    assert_eq!(eval_print("(into [] (take 3 (range)))")?, "[0 1 2]");
+    assert_eq!(eval_print("(= (into #{} [1 1 2]) #{1 2})")?, "true");
+    assert_eq!(
+        eval_print("(= (into {:a 1} [[:b 2]]) {:a 1 :b 2})")?,
+        "true"
+    );
+    assert_eq!(eval_print("(= (into {} {:a 1 :b 2}) {:a 1 :b 2})")?, "true");
+    let err = eval_print("(into {} [1])").unwrap_err();
+    assert!(matches!(err.inner(), MalError::ArgType { .. }));
+    Ok(())
+}
+
+#[test]
+pub fn testing_nested_updates() -> Result<(), MalError> {
+    assert_eq!(eval_print("(update {:a 1} :a + 10)")?, "{:a 11}");
+    assert_eq!(eval_print("(update {} :a (fn* [x] x))")?, "{:a nil}");
+    assert_eq!(eval_print("(update [1 2] 1 - 5)")?, "[1 -3]");
+    assert_eq!(eval_print("(get-in {:a {:b [1 2]}} [:a :b 1])")?, "2");
+    assert_eq!(eval_print("(get-in {:a 1} [:b :c])")?, "nil");
+    assert_eq!(eval_print("(get-in {:a 1} [:b :c] :none)")?, ":none");
+    assert_eq!(eval_print("(get-in {:a 1} [])")?, "{:a 1}");
+    assert_eq!(eval_print("(assoc-in {} [:a :b] 1)")?, "{:a {:b 1}}");
+    assert_eq!(
+        eval_print("(assoc-in {:a [0 1]} [:a 2] 2)")?,
+        "{:a [0 1 2]}"
+    );
+    assert_eq!(
+        eval_print("(update-in {:a {:b 1}} [:a :b] + 1 2)")?,
+        "{:a {:b 4}}"
+    );
+    let err = eval_print("(assoc-in {:a [0]} [:a 5] 1)").unwrap_err();
+    assert!(matches!(err.inner(), MalError::OutOfBounds { .. }));
+    let err = eval_print("(assoc-in [1] [-1] 5)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "-1 is out of bounds, index can't be negative"
+    );
+    let err = eval_print("(assoc-in {:a 1} [:a :b] 1)").unwrap_err();
+    assert!(matches!(err.inner(), MalError::ArgType { .. }));
+    Ok(())
+}
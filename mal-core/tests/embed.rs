@@ -0,0 +1,111 @@
+use std::{cell::RefCell, rc::Rc};
+
+use mal_core::{
+    embed::{self, IntoMal},
+    env::Env,
+    eval_str, rep,
+    types::MalType,
+    MalError,
+};
+
+#[test]
+pub fn testing_register_fn() -> Result<(), MalError> {
+    let env = Env::new();
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let log = calls.clone();
+    env.register_fn("log!", move |args, _| {
+        let message: String = embed::arg(args, 0)?;
+        log.borrow_mut().push(message);
+        Ok(log.borrow().len().to_string().into_mal())
+    });
+    assert_eq!(rep(r#"(log! "a")"#, &env)?, r#""1""#);
+    assert_eq!(rep(r#"(map log! ["b" "c"])"#, &env)?, r#"("2" "3")"#);
+    assert_eq!(*calls.borrow(), vec!["a", "b", "c"]);
+    assert_eq!(rep("(fn? log!)", &env)?, "true");
+    assert_eq!(rep("log!", &env)?, "log!");
+
+    let err = rep("(log! 1)", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::WrongType(_)));
+
+    // Closures are only equal to themselves
+    env.register_fn("other!", |_, _| Ok(().into_mal()));
+    assert_eq!(rep("(= log! log!)", &env)?, "true");
+    assert_eq!(rep("(= log! other!)", &env)?, "false");
+    // Closures are never equal to builtins, even under the same name
+    rep("(def! builtin-count count)", &env)?;
+    env.register_fn("count", |_, _| Ok(0.into_mal()));
+    assert_eq!(
+        rep("[(= builtin-count count) (= count builtin-count)]", &env)?,
+        "[false false]"
+    );
+    assert_eq!(rep("(get {builtin-count 1} count)", &env)?, "nil");
+
+    // Functions registered from a nested environment are defined in its namespace
+    let inner = Env::with_outer(env.clone());
+    inner.register_fn("inner!", |_, _| Ok(1.into_mal()));
+    inner.set_global("inner", 2);
+    assert_eq!(rep("[(inner!) inner]", &env)?, "[1 2]");
+    Ok(())
+}
+
+#[test]
+pub fn testing_globals() -> Result<(), MalError> {
+    let env = Env::new();
+    env.set_global("answer", 42);
+    env.set_global("ratio", 0.5);
+    env.set_global("name", "mal");
+    env.set_global("items", vec![1, 2, 3]);
+    env.set_global("missing", None::<i64>);
+    assert_eq!(
+        rep("[answer ratio name items missing]", &env)?,
+        r#"[42 0.5 "mal" [1 2 3] nil]"#
+    );
+
+    eval_str(
+        "(def! total (apply + items)) (def! names (list name \"lisp\"))",
+        &env,
+    )?;
+    assert_eq!(env.get_global::<i64>("total")?, 6);
+    assert_eq!(env.get_global::<f64>("total")?, 6.0);
+    assert_eq!(env.get_global::<Vec<String>>("names")?, vec!["mal", "lisp"]);
+    assert_eq!(env.get_global::<Option<i64>>("missing")?, None);
+    let value: Rc<dyn MalType> = env.get_global("items")?;
+    assert_eq!(value.to_string(), "[1 2 3]");
+
+    let err = env.get_global::<bool>("answer").unwrap_err();
+    assert!(matches!(err, MalError::WrongType(_)));
+    let err = env.get_global::<i64>("undefined").unwrap_err();
+    assert!(matches!(err, MalError::NotFound(_)));
+    Ok(())
+}
+
+#[test]
+pub fn testing_call() -> Result<(), MalError> {
+    let env = Env::new();
+    eval_str(
+        "(defn add [a b] (+ a b)) (defn greet [name] (str \"hi \" name))",
+        &env,
+    )?;
+    assert_eq!(env.call::<_, i64>("add", (1, 2))?, 3);
+    assert_eq!(env.call::<_, String>("greet", ("you",))?, "hi you");
+    assert_eq!(env.call::<_, i64>("+", ())?, 0);
+    assert_eq!(env.call::<_, Vec<i64>>("range", (3,))?, vec![0, 1, 2]);
+
+    let add = env.get_global("add")?;
+    assert_eq!(embed::call::<_, f64>(&add, (1.5, 1), &env)?, 2.5);
+
+    let err = env.call::<_, i64>("add", (1,)).unwrap_err();
+    assert!(matches!(err.inner(), MalError::WrongArity { .. }));
+    let err = env.call::<_, i64>("answer", ()).unwrap_err();
+    assert!(matches!(err, MalError::NotFound(_)));
+    Ok(())
+}
+
+#[test]
+pub fn testing_eval_str_in_namespace() -> Result<(), MalError> {
+    let env = Env::new();
+    let result = eval_str("(ns host) (def! x 1) (+ x 1)", &env)?;
+    assert_eq!(result.to_string(), "2");
+    assert_eq!(env.get_global::<i64>("host/x")?, 1);
+    Ok(())
+}
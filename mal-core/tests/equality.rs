@@ -0,0 +1,135 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+// One value of every type, sequences as well as integers and big integers holding the same
+// value are the only pairs of different values that are equal
+const VALUES: &[&str] = &[
+    "nil",
+    "true",
+    "false",
+    "1",
+    "1N",
+    "100000000000000000000",
+    "1.0",
+    "\"a\"",
+    "\\a",
+    "'a",
+    ":a",
+    "(list 1 2)",
+    "[1 2]",
+    "(lazy-seq (list 1 2))",
+    "{:a 1}",
+    "#{1}",
+    "#\"a\"",
+    "(atom 1)",
+    "(fn* () 1)",
+    "+",
+    "(try* (first 1) (catch* e e))",
+];
+
+const EQUAL_PAIRS: &[(&str, &str)] = &[
+    ("1", "1N"),
+    ("(list 1 2)", "[1 2]"),
+    ("(list 1 2)", "(lazy-seq (list 1 2))"),
+    ("[1 2]", "(lazy-seq (list 1 2))"),
+];
+
+#[test]
+pub fn testing_equality_of_each_pair_of_types() -> Result<(), MalError> {
+    let env = Env::new();
+    for (i, value) in VALUES.iter().enumerate() {
+        rep(&format!("(def! v{} {})", i, value), &env)?;
+    }
+    for (i, lhs) in VALUES.iter().enumerate() {
+        for (j, rhs) in VALUES.iter().enumerate() {
+            let expected =
+                i == j || EQUAL_PAIRS.contains(&(lhs, rhs)) || EQUAL_PAIRS.contains(&(rhs, lhs));
+            assert_eq!(
+                rep(&format!("(= v{} v{})", i, j), &env)?,
+                expected.to_string(),
+                "(= {} {})",
+                lhs,
+                rhs
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+pub fn testing_equality_of_hash_maps() -> Result<(), MalError> {
+    assert_eq!(eval_print("(= {} {})")?, String::from("true"));
+    assert_eq!(
+        eval_print("(= {:a 1 :b 2} {:b 2 :a 1})")?,
+        String::from("true")
+    );
+    assert_eq!(eval_print("(= {:a 1} {:a 2})")?, String::from("false"));
+    assert_eq!(eval_print("(= {:a 1} {:a 1 :b 2})")?, String::from("false"));
+    assert_eq!(eval_print("(= {:a 1 :b 2} {:a 1})")?, String::from("false"));
+    assert_eq!(
+        eval_print("(= {:a [1 {:b (list 2)}]} {:a (list 1 {:b [2]})})")?,
+        String::from("true")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_equality_of_sets() -> Result<(), MalError> {
+    assert_eq!(eval_print("(= #{} #{})")?, String::from("true"));
+    assert_eq!(eval_print("(= #{1 2} #{2 1})")?, String::from("true"));
+    assert_eq!(eval_print("(= #{1 2} #{1})")?, String::from("false"));
+    assert_eq!(
+        eval_print("(= #{[1 2]} #{(list 1 2)})")?,
+        String::from("true")
+    );
+    assert_eq!(eval_print("(= #{1} [1])")?, String::from("false"));
+    assert_eq!(
+        eval_print("(= {#{1 2} 1} {#{2 1} 1})")?,
+        String::from("true")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_equality_of_sequences() -> Result<(), MalError> {
+    assert_eq!(eval_print("(= (list) [])")?, String::from("true"));
+    assert_eq!(
+        eval_print("(= [1 [2 3]] (list 1 (list 2 3)))")?,
+        String::from("true")
+    );
+    assert_eq!(eval_print("(= [1 2] [1 2 3])")?, String::from("false"));
+    assert_eq!(eval_print("(= [1 2] [2 1])")?, String::from("false"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_identity_equality() -> Result<(), MalError> {
+    assert_eq!(eval_print("(= (atom 1) (atom 1))")?, String::from("false"));
+    assert_eq!(
+        eval_print("(let* (a (atom 1)) (= a a))")?,
+        String::from("true")
+    );
+    assert_eq!(
+        eval_print("(let* (a (atom 1) b a) (do (reset! a 2) (= a b)))")?,
+        String::from("true")
+    );
+    assert_eq!(
+        eval_print("(= (fn* () 1) (fn* () 1))")?,
+        String::from("false")
+    );
+    assert_eq!(
+        eval_print("(let* (f (fn* () 1)) (= f f))")?,
+        String::from("true")
+    );
+    assert_eq!(
+        eval_print("(let* (f (fn* () 1)) (= [f] [f]))")?,
+        String::from("true")
+    );
+    assert_eq!(eval_print("(= + +)")?, String::from("true"));
+    assert_eq!(eval_print("(= + -)")?, String::from("false"));
+    Ok(())
+}
@@ -0,0 +1,705 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+#[test]
+pub fn testing_float_arithmetic() -> Result<(), MalError> {
+    assert_eq!(eval_print("(+ 1.5 2.25)")?, String::from("3.75"));
+    assert_eq!(eval_print("(- 1.5 2.5)")?, String::from("-1.0"));
+    assert_eq!(eval_print("(* 1.5 2.0)")?, String::from("3.0"));
+    assert_eq!(eval_print("(/ 1.0 4.0)")?, String::from("0.25"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_mixed_arithmetic() -> Result<(), MalError> {
+    assert_eq!(eval_print("(+ 1 2)")?, String::from("3"));
+    assert_eq!(eval_print("(+ 1 0.5)")?, String::from("1.5"));
+    assert_eq!(eval_print("(* 2 .5)")?, String::from("1.0"));
+    assert_eq!(eval_print("(/ 1 2)")?, String::from("0"));
+    assert_eq!(eval_print("(/ 1 2.0)")?, String::from("0.5"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_mixed_comparison() -> Result<(), MalError> {
+    assert_eq!(eval_print("(< 1 1.5)")?, String::from("true"));
+    assert_eq!(eval_print("(<= 2.0 2)")?, String::from("true"));
+    assert_eq!(eval_print("(> 2 2.5)")?, String::from("false"));
+    assert_eq!(eval_print("(>= -1 -1.0)")?, String::from("true"));
+    assert_eq!(eval_print("(number? 1.5)")?, String::from("true"));
+    assert!(eval_print("(+ 1 \"a\")").is_err());
+    Ok(())
+}
+
+#[test]
+pub fn testing_variadic_arithmetic() -> Result<(), MalError> {
+    assert_eq!(eval_print("(+)")?, String::from("0"));
+    assert_eq!(eval_print("(*)")?, String::from("1"));
+    assert_eq!(eval_print("(+ 1 2 3 4)")?, String::from("10"));
+    assert_eq!(eval_print("(* 1 2 3 4)")?, String::from("24"));
+    assert_eq!(eval_print("(- 5)")?, String::from("-5"));
+    assert_eq!(eval_print("(- 10 1 2 3)")?, String::from("4"));
+    assert_eq!(eval_print("(/ 2.0)")?, String::from("0.5"));
+    assert_eq!(eval_print("(/ 100 2 5)")?, String::from("10"));
+    assert_eq!(eval_print("(+ 1 2 0.5)")?, String::from("3.5"));
+    assert!(eval_print("(-)").is_err());
+    assert!(eval_print("(/)").is_err());
+    Ok(())
+}
+
+#[test]
+pub fn testing_chained_comparison() -> Result<(), MalError> {
+    assert_eq!(eval_print("(< 1)")?, String::from("true"));
+    assert_eq!(eval_print("(< 1 2 3)")?, String::from("true"));
+    assert_eq!(eval_print("(< 1 3 2)")?, String::from("false"));
+    assert_eq!(eval_print("(<= 1 1 2)")?, String::from("true"));
+    assert_eq!(eval_print("(> 3 2 1)")?, String::from("true"));
+    assert_eq!(eval_print("(>= 3 3 4)")?, String::from("false"));
+    assert!(eval_print("(<)").is_err());
+    assert!(eval_print("(< 1 2 \"a\")").is_err());
+    Ok(())
+}
+
+#[test]
+pub fn testing_overflow_promotion() -> Result<(), MalError> {
+    assert_eq!(
+        eval_print("(* 9999999999 9999999999)")?,
+        String::from("99999999980000000001N")
+    );
+    assert_eq!(
+        eval_print("(+ 9223372036854775807 1)")?,
+        String::from("9223372036854775808N")
+    );
+    assert_eq!(
+        eval_print("(- -9223372036854775808 1)")?,
+        String::from("-9223372036854775809N")
+    );
+    assert_eq!(
+        eval_print("(/ -9223372036854775808 -1)")?,
+        String::from("9223372036854775808N")
+    );
+    assert_eq!(eval_print("(str (+ 1N 1))")?, String::from("\"2\""));
+    assert_eq!(eval_print("(+ 1N 0.5)")?, String::from("1.5"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_big_integer_comparison() -> Result<(), MalError> {
+    assert_eq!(eval_print("(= 1 1N)")?, String::from("true"));
+    assert_eq!(eval_print("(= 1N 2)")?, String::from("false"));
+    assert_eq!(
+        eval_print("(< 1 100000000000000000000 1e30)")?,
+        String::from("true")
+    );
+    assert_eq!(eval_print("(number? 1N)")?, String::from("true"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_division_by_zero() {
+    assert_eq!(eval_print("(/ 1 0)"), Err(MalError::DivisionByZero));
+    assert_eq!(eval_print("(/ 1N 0)"), Err(MalError::DivisionByZero));
+    assert_eq!(
+        eval_print("(try* (/ 1 0) (catch* e (str e)))"),
+        Ok(String::from("\"Division by zero\""))
+    );
+}
+
+#[test]
+pub fn testing_error_locations() {
+    let err = eval_print("(+ 1\n  (first 1))").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "<input>:2:3: `first`: argument 1 expected sequence, got 1"
+    );
+    assert_eq!(
+        err.location().unwrap().snippet(),
+        "  |\n2 |   (first 1))\n  |   ^^^^^^^^^"
+    );
+
+    let err = eval_print("(do (def! f (fn* (a) (+ a \"b\"))) (f 1))").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "<input>:1:22: `+`: argument 2 expected number, got \"b\""
+    );
+}
+
+#[test]
+pub fn testing_argument_type_errors() {
+    let err = eval_print("(+ 1 2 :a)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`+`: argument 3 expected number, got :a"
+    );
+    let err = eval_print("(< 1 \"2\" 3)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`<`: argument 2 expected number, got \"2\""
+    );
+    let err = eval_print("(- nil)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`-`: argument 1 expected number, got nil"
+    );
+    let err = eval_print("(deref 1)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`deref`: argument 1 expected atom, got 1"
+    );
+    let err = eval_print("(concat [1] (list 2) 3)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`concat`: argument 3 expected sequence, got 3"
+    );
+    let err = eval_print("(let* 1 2)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`let*`: argument 1 expected list or vector, got 1"
+    );
+    assert_eq!(
+        eval_print("(try* (first 1) (catch* e (str e)))"),
+        Ok(String::from(
+            "\"`first`: argument 1 expected sequence, got 1\""
+        ))
+    );
+}
+
+#[test]
+pub fn testing_parse_error_locations() {
+    let err = eval_print("(1\n [2 3").unwrap_err();
+    assert_eq!(err.to_string(), "<input>:2:2: Expected matching ']'.");
+
+    let err = eval_print("(read-string \"(1 2\")").unwrap_err();
+    assert_eq!(err.to_string(), "<input>:1:1: Expected matching ')'.");
+    assert_eq!(err.location().unwrap().source().text(), "(1 2");
+}
+
+#[test]
+pub fn testing_stack_traces() {
+    let env = Env::new();
+    rep("(def! f (fn* (x) (+ x \"a\")))", &env).unwrap();
+    rep("(def! g (fn* (x) (+ 1 (f x))))", &env).unwrap();
+    let err = rep("(g 1)", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::ArgType { .. }));
+    let frames = err.stack_trace().unwrap().frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].name(), Some("f"));
+    assert_eq!(frames[0].location().unwrap().to_string(), "<input>:1:23");
+    assert_eq!(frames[1].name(), Some("g"));
+    assert_eq!(frames[1].location().unwrap().to_string(), "<input>:1:1");
+
+    let err = rep("(map g [1])", &env).unwrap_err();
+    let frames = err.stack_trace().unwrap().frames();
+    assert_eq!(frames[1].name(), Some("g"));
+    assert!(frames[1].location().is_none());
+
+    assert!(rep("(+ 1 \"a\")", &env)
+        .unwrap_err()
+        .stack_trace()
+        .is_none());
+}
+
+#[test]
+pub fn testing_caught_stack_traces() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(def! f (fn* (x) (throw x)))", &env)?;
+    assert_eq!(
+        rep("(try* (f 1) (catch* e *stack-trace*))", &env)?,
+        String::from("\"  at f (<input>:1:7)\"")
+    );
+    assert_eq!(
+        rep("(try* (throw 1) (catch* e *stack-trace*))", &env)?,
+        String::from("nil")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_metadata() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(rep("(meta [1 2])", &env)?, String::from("nil"));
+    assert_eq!(
+        rep("(meta (with-meta [1 2] {:a 1}))", &env)?,
+        String::from("{:a 1}")
+    );
+    assert_eq!(
+        rep("(meta ^{:doc \"list\"} (list 1 2))", &env)?,
+        String::from("{:doc \"list\"}")
+    );
+    assert_eq!(
+        rep("(meta (with-meta {:k 1} \"abc\"))", &env)?,
+        String::from("\"abc\"")
+    );
+    assert_eq!(
+        rep("(meta (with-meta + {:builtin true}))", &env)?,
+        String::from("{:builtin true}")
+    );
+    assert_eq!(
+        rep("(meta (with-meta [1] nil))", &env)?,
+        String::from("nil")
+    );
+
+    rep("(def! f ^{:doc \"adds one\"} (fn* (x) (+ x 1)))", &env)?;
+    assert_eq!(rep("(meta f)", &env)?, String::from("{:doc \"adds one\"}"));
+    assert_eq!(rep("(f 1)", &env)?, String::from("2"));
+    rep("(def! g (with-meta f {:doc \"other\"}))", &env)?;
+    assert_eq!(rep("(meta f)", &env)?, String::from("{:doc \"adds one\"}"));
+    assert_eq!(rep("(meta g)", &env)?, String::from("{:doc \"other\"}"));
+
+    assert_eq!(
+        rep("(= [1 2] (with-meta [1 2] {:a 1}))", &env)?,
+        String::from("true")
+    );
+    assert_eq!(
+        rep("(= (list 1 2) (with-meta (list 1 2) {:a 1}))", &env)?,
+        String::from("true")
+    );
+    assert_eq!(
+        rep("(meta (assoc (with-meta {} {:a 1}) :k 2))", &env)?,
+        String::from("{:a 1}")
+    );
+    assert!(rep("(with-meta 1 {})", &env).is_err());
+    Ok(())
+}
+
+#[test]
+pub fn testing_hash_map_keys() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(def! m (hash-map \"a\" 1 'a 2 :a 3 1 4 [1 2] 5))", &env)?;
+    assert_eq!(rep("(count (keys m))", &env)?, String::from("5"));
+    assert_eq!(rep("(get m \"a\")", &env)?, String::from("1"));
+    assert_eq!(rep("(get m 'a)", &env)?, String::from("2"));
+    assert_eq!(rep("(get m :a)", &env)?, String::from("3"));
+    assert_eq!(rep("(get m 1)", &env)?, String::from("4"));
+    assert_eq!(rep("(get m \"1\")", &env)?, String::from("nil"));
+    assert_eq!(rep("(get m [1 2])", &env)?, String::from("5"));
+    assert_eq!(rep("(get m (list 1 2))", &env)?, String::from("5"));
+    assert_eq!(rep("(get m 1N)", &env)?, String::from("4"));
+    assert_eq!(rep("(contains? m 1)", &env)?, String::from("true"));
+    assert_eq!(rep("(contains? m 2)", &env)?, String::from("false"));
+    assert_eq!(
+        rep("(contains? (dissoc m [1 2] 'a) (list 1 2))", &env)?,
+        String::from("false")
+    );
+    assert_eq!(rep("(keys {1 :one})", &env)?, String::from("(1)"));
+    assert_eq!(
+        rep("(number? (first (keys {1 :one})))", &env)?,
+        String::from("true")
+    );
+    assert_eq!(rep("(keys {[1] :one})", &env)?, String::from("([1])"));
+    assert_eq!(rep("{{:a 1} :map}", &env)?, String::from("{{:a 1} :map}"));
+    assert_eq!(
+        rep("(get {{:a 1 :b 2} 1} {:b 2 :a 1})", &env)?,
+        String::from("1")
+    );
+    assert_eq!(
+        rep("(assoc {1 :one} 1N :two)", &env)?,
+        String::from("{1 :two}")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_persistent_collections() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(def! l (list 1 2 3))", &env)?;
+    rep("(def! v [1 2 3])", &env)?;
+    rep("(def! m {:a 1})", &env)?;
+    assert_eq!(rep("(cons 0 l)", &env)?, String::from("(0 1 2 3)"));
+    assert_eq!(rep("(conj l 4 5)", &env)?, String::from("(1 2 3 4 5)"));
+    assert_eq!(rep("(conj v 4 5)", &env)?, String::from("[1 2 3 4 5]"));
+    assert_eq!(rep("(rest v)", &env)?, String::from("(2 3)"));
+    assert_eq!(
+        rep("(concat l v l)", &env)?,
+        String::from("(1 2 3 1 2 3 1 2 3)")
+    );
+    assert_eq!(rep("(get (assoc m :b 2) :b)", &env)?, String::from("2"));
+    assert_eq!(rep("(dissoc m :a)", &env)?, String::from("{}"));
+    // The originals are left untouched
+    assert_eq!(rep("l", &env)?, String::from("(1 2 3)"));
+    assert_eq!(rep("v", &env)?, String::from("[1 2 3]"));
+    assert_eq!(rep("m", &env)?, String::from("{:a 1}"));
+
+    rep(
+        "(def! build (fn* (n acc) (if (= n 0) acc (build (- n 1) (conj acc n)))))",
+        &env,
+    )?;
+    assert_eq!(
+        rep("(count (build 20000 []))", &env)?,
+        String::from("20000")
+    );
+    assert_eq!(
+        rep("(nth (build 20000 []) 0)", &env)?,
+        String::from("20000")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_sets() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(def! s #{1 2 3})", &env)?;
+    assert_eq!(rep("#{(+ 1 1)}", &env)?, String::from("#{2}"));
+    assert_eq!(rep("(hash-set 1 1 1)", &env)?, String::from("#{1}"));
+    assert_eq!(rep("(hash-set)", &env)?, String::from("#{}"));
+    assert_eq!(rep("(set? s)", &env)?, String::from("true"));
+    assert_eq!(rep("(set? {})", &env)?, String::from("false"));
+    assert_eq!(rep("(count s)", &env)?, String::from("3"));
+    assert_eq!(rep("(empty? s)", &env)?, String::from("false"));
+    assert_eq!(rep("(empty? #{})", &env)?, String::from("true"));
+    assert_eq!(rep("(contains? s 2)", &env)?, String::from("true"));
+    assert_eq!(rep("(contains? s 2N)", &env)?, String::from("true"));
+    assert_eq!(rep("(contains? s 4)", &env)?, String::from("false"));
+    assert_eq!(rep("(contains? {:a 1} :a)", &env)?, String::from("true"));
+    assert_eq!(rep("(disj s 1 3 5)", &env)?, String::from("#{2}"));
+    assert_eq!(rep("(conj #{} 1 1)", &env)?, String::from("#{1}"));
+    assert_eq!(
+        rep("(= (conj s 4) #{1 2 3 4})", &env)?,
+        String::from("true")
+    );
+    assert_eq!(rep("(seq #{1})", &env)?, String::from("(1)"));
+    assert_eq!(rep("(count (seq s))", &env)?, String::from("3"));
+    assert_eq!(
+        rep("(meta (with-meta #{} {:a 1}))", &env)?,
+        String::from("{:a 1}")
+    );
+    assert_eq!(rep("s", &env)?.len(), "#{1 2 3}".len());
+    let err = rep("(contains? [1] 0)", &env).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`contains?`: argument 1 expected hash-map or set, got [1]"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_dispatch_macros() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(rep("(#(+ % %2) 1 2)", &env)?, String::from("3"));
+    assert_eq!(rep("(#(* 2 %) 4)", &env)?, String::from("8"));
+    assert_eq!(
+        rep("(#(list %1 %&) 1 2 3)", &env)?,
+        String::from("(1 (2 3))")
+    );
+    assert_eq!(rep("(map #(+ % 1) [1 2])", &env)?, String::from("(2 3)"));
+    assert_eq!(rep("(+ 1 #_2 3)", &env)?, String::from("4"));
+    assert_eq!(
+        rep(r##"(pr-str #"\d+")"##, &env)?,
+        String::from(r##""#\"\\d+\"""##)
+    );
+    assert_eq!(rep(r##"(str #"\d+")"##, &env)?, String::from(r#""\\d+""#));
+    assert_eq!(
+        rep(r##"(let* (r #"a") (= r r))"##, &env)?,
+        String::from("true")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_characters() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(rep(r"(char? \a)", &env)?, String::from("true"));
+    assert_eq!(rep(r#"(char? "a")"#, &env)?, String::from("false"));
+    assert_eq!(rep("(char 955)", &env)?, String::from(r"\λ"));
+    assert_eq!(rep(r"(char \a)", &env)?, String::from(r"\a"));
+    assert_eq!(rep(r"(int \a)", &env)?, String::from("97"));
+    assert_eq!(rep("(int 2.7)", &env)?, String::from("2"));
+    assert_eq!(rep(r"(= \a (char 97))", &env)?, String::from("true"));
+    assert_eq!(rep(r#"(seq "ab")"#, &env)?, String::from(r"(\a \b)"));
+    assert_eq!(rep(r"(str \a \space \b)", &env)?, String::from(r#""a b""#));
+    assert_eq!(
+        rep(r"(pr-str \newline)", &env)?,
+        String::from(r#""\\newline""#)
+    );
+    assert_eq!(
+        rep("(char -1)", &env).unwrap_err().inner().to_string(),
+        "-1 is not a valid character code"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_loop_and_recur() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(
+        rep(
+            "(loop* [i 0 acc 0] (if (< i 100000) (recur (+ i 1) (+ acc i)) acc))",
+            &env
+        )?,
+        String::from("4999950000")
+    );
+    rep(
+        "(def! sum (fn* [n acc] (if (= n 0) acc (recur (- n 1) (+ acc n)))))",
+        &env,
+    )?;
+    assert_eq!(rep("(sum 100000 0)", &env)?, String::from("5000050000"));
+    assert_eq!(
+        rep(
+            "(loop* [x 0] (let* [y (+ x 1)] (if (< y 5) (do (recur y)) y)))",
+            &env
+        )?,
+        String::from("5")
+    );
+    assert_eq!(
+        rep(
+            "((fn* [& xs] (if (empty? xs) :done (recur (rest xs)))) 1 2 3)",
+            &env
+        )?,
+        String::from(":done")
+    );
+    assert_eq!(
+        rep(
+            "(loop* [x 3] (if (> x 0) (recur (loop* [y x] (- y 1))) x))",
+            &env
+        )?,
+        String::from("0")
+    );
+    rep("(defmacro! unless (fn* [c body] `(if ~c nil ~body)))", &env)?;
+    assert_eq!(
+        rep("(loop* [x 0] (unless (= x 3) (recur (+ x 1))))", &env)?,
+        String::from("nil")
+    );
+    // Closures created again from the same form still recur
+    rep(
+        "(def! countdown (fn* [n] (fn* [x] (if (> x 0) (recur (- x 1)) n))))",
+        &env,
+    )?;
+    assert_eq!(rep("((countdown 1) 3)", &env)?, String::from("1"));
+    assert_eq!(rep("((countdown 2) 3)", &env)?, String::from("2"));
+    assert_eq!(rep("(fn? (fn* [] (do)))", &env)?, String::from("true"));
+    assert_eq!(
+        rep("(fn? (loop* [] (fn* [x] (do))))", &env)?,
+        String::from("true")
+    );
+    // Functions called by builtins recur to themselves
+    assert_eq!(
+        rep(
+            "(map (fn* [x] (if (> x 0) (recur (- x 1)) :done)) [0 3])",
+            &env
+        )?,
+        String::from("(:done :done)")
+    );
+    // Macros are only expanded when the body is evaluated
+    rep("(def! expansions (atom 0))", &env)?;
+    rep(
+        "(defmacro! counted (fn* [] (do (swap! expansions + 1) nil)))",
+        &env,
+    )?;
+    rep("(def! f (fn* [] (counted)))", &env)?;
+    assert_eq!(rep("@expansions", &env)?, String::from("0"));
+    rep("(defmacro! later (fn* [x] `(recur ~x)))", &env)?;
+    rep("(def! g (fn* [x] (if (> x 0) (later (- x 1)) x)))", &env)?;
+    assert_eq!(rep("(g 3)", &env)?, String::from("0"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_recur_errors() {
+    let env = Env::new();
+    let not_in_tail = [
+        "(loop* [i 0] (+ 1 (recur i)))",
+        "((fn* [x] (do (recur x) 1)) 1)",
+        "(loop* [i 0] (if (recur i) 1 2))",
+        "(loop* [i 0] (let* [x (recur i)] x))",
+        "(loop* [i 0] (try* (recur 1) (catch* e e)))",
+        "(recur 1)",
+        "(loop* [i 0] [(recur 1)])",
+        "(loop* [i 0] (eval '(recur 1)))",
+    ];
+    for input in not_in_tail {
+        let err = rep(input, &env).unwrap_err();
+        assert!(matches!(err.inner(), MalError::RecurNotInTail), "{}", input);
+    }
+    assert_eq!(
+        rep("(loop* [i 0] (recur))", &env)
+            .unwrap_err()
+            .inner()
+            .to_string(),
+        "`recur` expected 1 argument, got 0"
+    );
+    assert_eq!(
+        rep("((fn* [a b] (if a (recur nil) b)) 1 2)", &env)
+            .unwrap_err()
+            .inner()
+            .to_string(),
+        "`recur` expected 2 arguments, got 1"
+    );
+}
+
+#[test]
+pub fn testing_multi_arity_functions() -> Result<(), MalError> {
+    let env = Env::new();
+    rep(
+        "(def! f (fn* ([] :none) ([a] [:one a]) ([a b] [:two a b]) ([a b & more] [:many more])))",
+        &env,
+    )?;
+    assert_eq!(rep("(f)", &env)?, String::from(":none"));
+    assert_eq!(rep("(f 1)", &env)?, String::from("[:one 1]"));
+    assert_eq!(rep("(f 1 2)", &env)?, String::from("[:two 1 2]"));
+    assert_eq!(rep("(f 1 2 3 4)", &env)?, String::from("[:many (3 4)]"));
+    rep(
+        "(def! sum (fn* ([xs] (sum 0 xs)) ([acc xs] (if (empty? xs) acc (recur (+ acc (first xs)) (rest xs))))))",
+        &env,
+    )?;
+    assert_eq!(rep("(sum [1 2 3])", &env)?, String::from("6"));
+    assert_eq!(
+        rep("((fn* [& xs] xs) 'a (list 1 2))", &env)?,
+        String::from("(a (1 2))")
+    );
+    assert!(rep("((fn* ([a] a)) 1 2)", &env).is_err());
+    let invalid = [
+        "(fn* ([a] 1) ([b] 2))",
+        "(fn* ([& a] 1) ([& b] 2))",
+        "(fn* ([a b c] 1) ([a & b] 2))",
+    ];
+    for input in invalid {
+        let err = rep(input, &env).unwrap_err();
+        assert!(matches!(err.inner(), MalError::InvalidFn(_)), "{}", input);
+    }
+    Ok(())
+}
+
+#[test]
+pub fn testing_destructuring() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(
+        rep(
+            "(let* [[a [b c] & rest :as all] [1 [2 3] 4 5]] [a b c rest all])",
+            &env
+        )?,
+        String::from("[1 2 3 (4 5) [1 [2 3] 4 5]]")
+    );
+    assert_eq!(
+        rep("(let* [[a b] [1] [c] nil] [a b c])", &env)?,
+        String::from("[1 nil nil]")
+    );
+    assert_eq!(
+        rep(
+            "(let* [{:keys [x y z] :or {y 2 z (+ x 10)} :as m} {:x 1 :z nil}] [x y z (contains? m :z)])",
+            &env
+        )?,
+        String::from("[1 2 nil true]")
+    );
+    assert_eq!(
+        rep(
+            r#"(let* [{:strs [a] :syms [b] c :c [d] :d} (hash-map "a" 1 'b 2 :c 3 :d [4])] [a b c d])"#,
+            &env
+        )?,
+        String::from("[1 2 3 4]")
+    );
+    assert_eq!(
+        rep("(let* [{:keys [a]} nil] a)", &env)?,
+        String::from("nil")
+    );
+    rep(
+        "(def! point (fn* [{:keys [x y]} [dx dy]] [(+ x dx) (+ y dy)]))",
+        &env,
+    )?;
+    assert_eq!(
+        rep("(point {:x 1 :y 2} [10 20])", &env)?,
+        String::from("[11 22]")
+    );
+    assert_eq!(
+        rep(
+            "(loop* [[x & xs] [1 2 3] acc 0] (if x (recur xs (+ acc x)) acc))",
+            &env
+        )?,
+        String::from("6")
+    );
+    // Any sequence can be taken apart, lazy ones only as far as the pattern needs
+    assert_eq!(
+        rep("(let* [[a b & r] (range)] [a b (take 2 r)])", &env)?,
+        String::from("[0 1 (2 3)]")
+    );
+    assert_eq!(
+        rep(r#"(let* [[a b & r] "xyz"] [a b r])"#, &env)?,
+        String::from(r"[\x \y (\z)]")
+    );
+    assert_eq!(
+        rep("(let* [[[k v]] {:a 1} [x & r] #{1}] [k v x r])", &env)?,
+        String::from("[:a 1 1 ()]")
+    );
+    let err = rep("(let* [1 2] 1)", &env).unwrap_err();
+    assert_eq!(err.inner().to_string(), "`1` is not a valid binding form");
+    assert!(rep("(let* [{:keys [a]} [1]] a)", &env).is_err());
+    Ok(())
+}
+
+#[test]
+pub fn testing_arity_errors() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(def! f (fn* [a b] a))", &env)?;
+    rep("(def! g (fn* ([] 0) ([a] a) ([a b c & more] c)))", &env)?;
+    let cases = [
+        ("(f 1)", "`f` expected 2 arguments, got 1"),
+        ("(f 1 2 3)", "`f` expected 2 arguments, got 3"),
+        (
+            "(g 1 2)",
+            "`g` expected 0, 1 or at least 3 arguments, got 2",
+        ),
+        (
+            "((fn* [] 1) 1)",
+            "`<anonymous>` expected 0 arguments, got 1",
+        ),
+        ("(count)", "`count` expected 1 argument, got 0"),
+        (
+            "((fn* ([] 0) ([a] 1)) 1 2)",
+            "`<anonymous>` expected 0 or 1 arguments, got 2",
+        ),
+        ("(gensym 1 2)", "`gensym` expected 0 or 1 arguments, got 2"),
+        ("(if true)", "`if` expected 2 or 3 arguments, got 1"),
+        ("(<)", "`<` expected at least 1 argument, got 0"),
+        ("(-)", "`-` expected at least 1 argument, got 0"),
+    ];
+    for (input, message) in cases {
+        let err = rep(input, &env).unwrap_err();
+        assert_eq!(err.inner().to_string(), message);
+    }
+    assert_eq!(rep("(g 1 2 3)", &env)?, String::from("3"));
+    Ok(())
+}
+
+#[test]
+pub fn testing_invalid_arguments() {
+    let env = Env::new();
+    let cases = [
+        (
+            "(nth [1 2] -1)",
+            "-1 is out of bounds, index can't be negative",
+        ),
+        ("(apply +)", "`apply` expected at least 2 arguments, got 1"),
+        (
+            "(hash-map 1)",
+            "`hash-map` expected an even number of arguments, got 1",
+        ),
+        (
+            "(assoc {} 1)",
+            "`assoc` expected an odd number of arguments, got 2",
+        ),
+        ("(conj [1])", "`conj` expected at least 2 arguments, got 1"),
+        (
+            "(let* [a] 1)",
+            "`let*`: argument 1 expected even number of binding forms, got [a]",
+        ),
+        (
+            "(quasiquote (unquote))",
+            "`unquote` expected 1 argument, got 0",
+        ),
+        (
+            "(quasiquote ((splice-unquote)))",
+            "`splice-unquote` expected 1 argument, got 0",
+        ),
+        (
+            "(try* (throw 1) (catch e e))",
+            "`try*`: argument 2 expected `(catch* symbol body)` form, got (catch e e)",
+        ),
+    ];
+    for (input, message) in cases {
+        let err = rep(input, &env).unwrap_err();
+        assert_eq!(err.inner().to_string(), message, "{}", input);
+    }
+}
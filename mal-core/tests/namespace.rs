@@ -0,0 +1,136 @@
+use std::{fs, path::PathBuf, process, rc::Rc};
+
+use mal_core::{
+    env::{self, Env},
+    eval_source,
+    reader::Source,
+    rep, MalError,
+};
+
+/// Environment loading namespaces from a fresh directory holding the given files.
+fn env_with_files(test: &str, files: &[(&str, &str)]) -> Rc<Env> {
+    let dir = std::env::temp_dir().join(format!("mal-namespace-{}-{}", process::id(), test));
+    let _ = fs::remove_dir_all(&dir);
+    for (path, text) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+    let env = Env::new();
+    env.set_load_path(vec![PathBuf::from(&dir)]);
+    env
+}
+
+#[test]
+pub fn testing_qualified_symbols() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(rep(r#"(str/join "," ["a" "b"])"#, &env)?, r#""a,b""#);
+    assert_eq!(rep("(str/upper-case \"a\")", &env)?, r#""A""#);
+    let err = rep("(str/nope)", &env).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`str/nope` not found in current scope."
+    );
+    let err = rep("(join)", &env).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`join` not found in current scope."
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_require() -> Result<(), MalError> {
+    let env = env_with_files(
+        "require",
+        &[
+            (
+                "my/util_lib.mal",
+                "(ns my.util-lib) (def! twice (fn* [x] (* 2 x))) (def! answer 42)",
+            ),
+            (
+                "my/app.mal",
+                "(ns my.app (:require [my.util-lib :as u])) (def! run (fn* [] (u/twice 3)))",
+            ),
+        ],
+    );
+    assert_eq!(rep("(require 'my.util-lib)", &env)?, "nil");
+    assert_eq!(rep("(my.util-lib/twice 2)", &env)?, "4");
+    rep("(require '[my.util-lib :as util :refer [answer]])", &env)?;
+    assert_eq!(rep("(util/twice answer)", &env)?, "84");
+    let err = rep("twice", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::NotFound(_)));
+
+    rep("(require '[my.app :refer :all])", &env)?;
+    assert_eq!(rep("(run)", &env)?, "6");
+    let err = rep("u/twice", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::NotFound(_)));
+
+    rep("(refer 'my.util-lib :only '[twice])", &env)?;
+    assert_eq!(rep("(twice 5)", &env)?, "10");
+    Ok(())
+}
+
+#[test]
+pub fn testing_require_loads_once() -> Result<(), MalError> {
+    let env = env_with_files(
+        "once",
+        &[("counter.mal", "(ns counter) (swap! user/loads user/inc)")],
+    );
+    rep("(def! inc (fn* [x] (+ x 1)))", &env)?;
+    rep("(def! loads (atom 0))", &env)?;
+    rep("(require 'counter)", &env)?;
+    rep("(require 'counter 'counter)", &env)?;
+    assert_eq!(rep("@loads", &env)?, "1");
+    Ok(())
+}
+
+#[test]
+pub fn testing_require_errors() -> Result<(), MalError> {
+    let env = env_with_files(
+        "errors",
+        &[
+            ("a.mal", "(ns a (:require b))"),
+            ("b.mal", "(ns b (:require a))"),
+            ("broken.mal", "(ns broken) (def! x 1) (undefined)"),
+        ],
+    );
+    let err = rep("(require 'a)", &env).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "Namespace `a` is required while it's being loaded"
+    );
+    let err = rep("(require 'missing.ns)", &env).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "Namespace `missing.ns` not found in the load path"
+    );
+    let err = rep("(require \"a\")", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::InvalidRequire(_)));
+    let err = rep("(require 'broken)", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::NotFound(_)));
+    assert!(rep("broken/x", &env).is_err());
+    let err = rep("(refer 'broken)", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::NamespaceNotFound(_)));
+    Ok(())
+}
+
+#[test]
+pub fn testing_ns() -> Result<(), MalError> {
+    let env = Env::new();
+    assert_eq!(env.namespace_name(), Some("user"));
+    let source = Rc::from(Source::from(
+        "(def! x 1) (ns other) (def! x 2) (def! y (+ x user/x))",
+    ));
+    assert_eq!(eval_source(&source, &env)?.to_string(), "3");
+    let other = env::current_namespace(&env).unwrap();
+    assert_eq!(other.namespace_name(), Some("other"));
+    assert_eq!(rep("x", &env)?, "1");
+    assert_eq!(rep("x", &other)?, "2");
+    assert_eq!(rep("other/y", &env)?, "3");
+
+    rep("(ns user)", &other)?;
+    let user = env::current_namespace(&env).unwrap();
+    assert!(Rc::ptr_eq(&user, &env));
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+fn expand(form: &str) -> Result<String, MalError> {
+    eval_print(&format!("(macroexpand {})", form))
+}
+
+#[test]
+pub fn testing_conditionals() -> Result<(), MalError> {
+    assert_eq!(expand("(when a b c)")?, "(if a (do b c) nil)");
+    assert_eq!(expand("(when-not a b)")?, "(if a nil (do b))");
+    assert_eq!(eval_print("(when true 1 2)")?, "2");
+    assert_eq!(eval_print("(when-not true 1)")?, "nil");
+    assert_eq!(expand("(cond a 1 b 2)")?, "(if a 1 (cond b 2))");
+    assert_eq!(eval_print("(cond false 1 :else 2)")?, "2");
+    assert_eq!(eval_print("(not nil)")?, "true");
+
+    let expanded = expand("(if-let [x (f)] x :none)")?;
+    assert!(expanded.starts_with("(let* [if_let__"));
+    assert!(expanded.contains("(let* [x if_let__"));
+    assert_eq!(eval_print("(if-let [[a b] [1 2]] (+ a b) :none)")?, "3");
+    assert_eq!(eval_print("(if-let [x false] x :none)")?, ":none");
+    assert_eq!(eval_print("(if-let [x nil] x)")?, "nil");
+    assert_eq!(eval_print("(when-let [x 5] 1 x)")?, "5");
+    assert_eq!(eval_print("(when-let [x nil] 1 x)")?, "nil");
+    Ok(())
+}
+
+#[test]
+pub fn testing_and_or() -> Result<(), MalError> {
+    assert_eq!(expand("(and)")?, "true");
+    assert_eq!(expand("(and a)")?, "a");
+    assert!(expand("(and a b)")?.starts_with("(let* [and__"));
+    assert_eq!(expand("(or)")?, "nil");
+    assert_eq!(expand("(or a)")?, "a");
+    assert!(expand("(or a b)")?.starts_with("(let* [or__"));
+    assert_eq!(eval_print("(and 1 nil 3)")?, "nil");
+    assert_eq!(eval_print("(and 1 2 3)")?, "3");
+    assert_eq!(eval_print("(or nil false 3)")?, "3");
+    assert_eq!(eval_print("(or nil false)")?, "false");
+    // Arguments are evaluated once, and only until the result is known
+    assert_eq!(
+        eval_print("(let [a (atom 0)] (or (swap! a + 1) (swap! a + 1)) @a)")?,
+        "1"
+    );
+    assert_eq!(eval_print("(let [x 1] (or false x))")?, "1");
+    Ok(())
+}
+
+#[test]
+pub fn testing_threading() -> Result<(), MalError> {
+    assert_eq!(expand("(-> x)")?, "x");
+    assert_eq!(expand("(-> x (f a) g)")?, "(g (f x a))");
+    assert_eq!(expand("(->> x (f a) g)")?, "(g (f a x))");
+    assert_eq!(eval_print("(-> 1 (+ 2) (- 10) list)")?, "(-7)");
+    assert_eq!(eval_print("(->> 1 (- 10) list)")?, "(9)");
+    Ok(())
+}
+
+#[test]
+pub fn testing_definitions() -> Result<(), MalError> {
+    assert_eq!(expand("(defn f [x] a b)")?, "(def! f (fn* [x] (do a b)))");
+    assert_eq!(
+        expand("(defn f \"doc\" [x] a)")?,
+        "(def! f (fn* [x] (do a)))"
+    );
+    assert_eq!(
+        expand("(defn f ([] a) ([x] b c))")?,
+        "(def! f (fn* ([] (do a)) ([x] (do b c))))"
+    );
+    assert_eq!(expand("(let [x 1] a b)")?, "(let* [x 1] (do a b))");
+
+    let env = Env::new();
+    rep("(defn twice \"Doubles x.\" [x] (* 2 x))", &env)?;
+    assert_eq!(rep("(twice 4)", &env)?, "8");
+    rep(
+        "(defn arities ([] 0) ([x] (inc x)) ([x & more] (count more)))",
+        &env,
+    )?;
+    rep("(defn inc [x] (+ x 1))", &env)?;
+    assert_eq!(
+        rep("[(arities) (arities 1) (arities 1 2 3)]", &env)?,
+        "[0 2 2]"
+    );
+    assert_eq!(rep("(let [[a b] [1 2] c 3] (+ a b c))", &env)?, "6");
+    Ok(())
+}
+
+#[test]
+pub fn testing_loops() -> Result<(), MalError> {
+    assert!(expand("(dotimes [i n] a)")?.starts_with("(let* [limit__"));
+    assert!(expand("(doseq [x xs] a)")?.starts_with("(loop* [seq__"));
+    assert_eq!(
+        eval_print("(let [a (atom 0)] (dotimes [i 5] (swap! a + i)) @a)")?,
+        "10"
+    );
+    assert_eq!(eval_print("(dotimes [i 0] (throw \"never\"))")?, "nil");
+    assert_eq!(
+        eval_print(
+            "(let [a (atom [])] (doseq [[k v] {:a 1}] (swap! a conj k v)) (doseq [x (take 2 (range))] (swap! a conj x)) @a)"
+        )?,
+        "[:a 1 0 1]"
+    );
+    assert_eq!(eval_print("(doseq [x nil] (throw \"never\"))")?, "nil");
+    // Macros keep `recur` in tail position
+    assert_eq!(
+        eval_print("(loop* [i 0] (when (< i 3) (recur (+ i 1))))")?,
+        "nil"
+    );
+    assert_eq!(
+        eval_print("((fn* [n acc] (if (and (> n 0) true) (recur (- n 1) (+ acc n)) acc)) 100 0)")?,
+        "5050"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_empty_bodies() -> Result<(), MalError> {
+    assert_eq!(eval_print("(do)")?, "nil");
+    assert_eq!(eval_print("(when true)")?, "nil");
+    assert_eq!(eval_print("(when-not false)")?, "nil");
+    assert_eq!(eval_print("(when-let [x 1])")?, "nil");
+    assert_eq!(eval_print("(let [x 1])")?, "nil");
+    assert_eq!(eval_print("(dotimes [i 2])")?, "nil");
+    assert_eq!(eval_print("(doseq [x [1 2]])")?, "nil");
+    let env = Env::new();
+    rep("(defn noop [])", &env)?;
+    rep("(defn noops ([]) ([x]))", &env)?;
+    assert_eq!(rep("[(noop) (noops) (noops 1)]", &env)?, "[nil nil nil]");
+    Ok(())
+}
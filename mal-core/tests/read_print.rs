@@ -3,7 +3,7 @@ use mal_core::{self, reader::ParseError};
 fn read_print(input: &str) -> Result<String, ParseError> {
     match mal_core::read(input) {
         Ok(ast) => Ok(format!("{:?}", ast)),
-        Err(ParseError::EOF) => Ok(String::new()),
+        Err(ParseError::EOF(_)) => Ok(String::new()),
         Err(err) => Err(err),
     }
 }
@@ -17,6 +17,32 @@ pub fn testing_read_of_numbers() -> Result<(), ParseError> {
     Ok(())
 }
 
+#[test]
+pub fn testing_read_of_big_integers() -> Result<(), ParseError> {
+    assert_eq!(read_print("1N")?, String::from("1N"));
+    assert_eq!(read_print("-42N")?, String::from("-42N"));
+    assert_eq!(
+        read_print("123456789012345678901234567890")?,
+        String::from("123456789012345678901234567890N")
+    );
+    assert_eq!(
+        read_print("9223372036854775807")?,
+        String::from("9223372036854775807")
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_read_of_floats() -> Result<(), ParseError> {
+    assert_eq!(read_print("1.5")?, String::from("1.5"));
+    assert_eq!(read_print("-1.5")?, String::from("-1.5"));
+    assert_eq!(read_print(".5")?, String::from("0.5"));
+    assert_eq!(read_print("2.")?, String::from("2.0"));
+    assert_eq!(read_print("-2e10")?, String::from("-20000000000.0"));
+    assert_eq!(read_print("1.5E-3")?, String::from("0.0015"));
+    Ok(())
+}
+
 #[test]
 pub fn testing_read_of_symbols() -> Result<(), ParseError> {
     assert_eq!(read_print("+")?, String::from("+"));
@@ -219,12 +245,94 @@ pub fn testing_read_of_deref() -> Result<(), ParseError> {
 }
 
 #[test]
-#[should_panic]
-pub fn testing_read_of_metadata() {
+pub fn testing_read_of_metadata() -> Result<(), ParseError> {
     assert_eq!(
-        read_print(r#"^{"a" 1} [1 2 3]"#).unwrap(),
+        read_print(r#"^{"a" 1} [1 2 3]"#)?,
         String::from(r#"(with-meta [1 2 3] {"a" 1})"#)
     );
+    Ok(())
+}
+
+#[test]
+pub fn testing_read_of_sets() -> Result<(), ParseError> {
+    assert_eq!(read_print("#{}")?, String::from("#{}"));
+    assert_eq!(read_print("#{ 1 }")?, String::from("#{1}"));
+    assert_eq!(read_print("#{[1 2] [1 2]}")?, String::from("#{[1 2]}"));
+    assert_eq!(read_print("[#{:a}]")?, String::from("[#{:a}]"));
+    assert!(matches!(
+        read_print("#{1 2"),
+        Err(ParseError::UnbalancedMap(_))
+    ));
+    Ok(())
+}
+
+#[test]
+pub fn testing_read_of_characters() -> Result<(), ParseError> {
+    assert_eq!(read_print(r"\a")?, String::from(r"\a"));
+    assert_eq!(read_print(r"[\a\b]")?, String::from(r"[\a \b]"));
+    assert_eq!(read_print(r"(\( \))")?, String::from(r"(\( \))"));
+    assert_eq!(read_print(r"\newline")?, String::from(r"\newline"));
+    assert_eq!(read_print(r"\space")?, String::from(r"\space"));
+    assert_eq!(read_print(r"\tab")?, String::from(r"\tab"));
+    assert_eq!(read_print(r"\u03bb")?, String::from(r"\λ"));
+    assert_eq!(read_print(r"\λ")?, String::from(r"\λ"));
+    assert!(matches!(
+        read_print(r"\newlin"),
+        Err(ParseError::InvalidChar(..))
+    ));
+    assert!(matches!(
+        read_print(r"\ud800"),
+        Err(ParseError::InvalidChar(..))
+    ));
+    Ok(())
+}
+
+#[test]
+pub fn testing_read_of_discarded_forms() -> Result<(), ParseError> {
+    assert_eq!(read_print("#_1 2")?, String::from("2"));
+    assert_eq!(read_print("(1 #_(2 3) 4)")?, String::from("(1 4)"));
+    assert_eq!(read_print("[1 #_2]")?, String::from("[1]"));
+    assert_eq!(read_print("(#_ #_ 1 2)")?, String::from("()"));
+    assert_eq!(read_print("#_1")?, String::from(""));
+    Ok(())
+}
+
+#[test]
+pub fn testing_read_of_fn_literals() -> Result<(), ParseError> {
+    assert_eq!(read_print("#(+ 1 2)")?, String::from("(fn* () (+ 1 2))"));
+    assert_eq!(
+        read_print("#(+ % %2)")?,
+        String::from("(fn* (%1 %2) (+ %1 %2))")
+    );
+    assert_eq!(
+        read_print("#(list %3 [%] %&)")?,
+        String::from("(fn* (%1 %2 %3 & %&) (list %3 [%1] %&))")
+    );
+    assert!(matches!(
+        read_print("#(#(%))"),
+        Err(ParseError::NestedFnLiteral(_))
+    ));
+    assert!(matches!(
+        read_print("#(+ 1"),
+        Err(ParseError::UnbalancedList(_))
+    ));
+    Ok(())
+}
+
+#[test]
+pub fn testing_read_of_regexes() -> Result<(), ParseError> {
+    assert_eq!(read_print(r#"#"a+b""#)?, String::from(r#"#"a+b""#));
+    assert_eq!(read_print(r#"#"\d\.\"""#)?, String::from(r#"#"\d\.\"""#));
+    assert_eq!(read_print(r#"[#"" 1]"#)?, String::from(r#"[#"" 1]"#));
+    assert!(matches!(
+        read_print(r#"#"(""#),
+        Err(ParseError::InvalidRegex(..))
+    ));
+    assert!(matches!(
+        read_print(r#"#"abc"#),
+        Err(ParseError::UnbalancedString(..))
+    ));
+    Ok(())
 }
 
 #[test]
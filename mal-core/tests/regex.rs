@@ -0,0 +1,114 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+#[test]
+pub fn testing_re_find() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(re-find #"\d+" "ab123cd45")"#)?, r#""123""#);
+    assert_eq!(
+        eval_print(r#"(re-find #"(\w)(\d)?" "a1")"#)?,
+        r#"["a1" "a" "1"]"#
+    );
+    assert_eq!(
+        eval_print(r#"(re-find #"(a)|(b)" "b")"#)?,
+        r#"["b" nil "b"]"#
+    );
+    assert_eq!(eval_print(r#"(re-find #"x" "abc")"#)?, "nil");
+    Ok(())
+}
+
+#[test]
+pub fn testing_re_matches() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(re-matches #"a|ab" "ab")"#)?, r#""ab""#);
+    assert_eq!(eval_print(r#"(re-matches #"\d+" "12a")"#)?, "nil");
+    assert_eq!(
+        eval_print(r#"(re-matches #"(\d+)-(\d+)" "1-23")"#)?,
+        r#"["1-23" "1" "23"]"#
+    );
+    assert_eq!(
+        eval_print(
+            r#"(let* [re #"\d+"] [(re-matches re "12") (re-matches re "1a") (re-find re "1a")])"#
+        )?,
+        r#"["12" nil "1"]"#
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_re_seq() -> Result<(), MalError> {
+    assert_eq!(
+        eval_print(r#"(re-seq #"\d" "a1b2c3")"#)?,
+        r#"("1" "2" "3")"#
+    );
+    assert_eq!(eval_print(r#"(re-seq #"x*" "ab")"#)?, r#"("" "" "")"#);
+    assert_eq!(eval_print(r#"(re-seq #"x" "ab")"#)?, "()");
+    Ok(())
+}
+
+#[test]
+pub fn testing_re_matcher() -> Result<(), MalError> {
+    let env = Env::new();
+    rep(r#"(def! m (re-matcher #"(\w)=(\d)" "a=1 b=2"))"#, &env)?;
+    assert_eq!(rep("(re-find m)", &env)?, r#"["a=1" "a" "1"]"#);
+    assert_eq!(rep("(re-groups m)", &env)?, r#"["a=1" "a" "1"]"#);
+    assert_eq!(rep("(re-find m)", &env)?, r#"["b=2" "b" "2"]"#);
+    assert_eq!(rep("(re-find m)", &env)?, "nil");
+    let err = rep("(re-groups m)", &env).unwrap_err();
+    assert_eq!(err.inner().to_string(), "No match found");
+    Ok(())
+}
+
+#[test]
+pub fn testing_re_pattern() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(re-pattern "a\"b")"#)?, r#"#"a\"b""#);
+    assert_eq!(
+        eval_print(r#"(re-find (re-pattern "b+") "abbc")"#)?,
+        r#""bb""#
+    );
+    assert_eq!(
+        eval_print(r#"(= (re-pattern "a") (re-pattern "a"))"#)?,
+        "true"
+    );
+    assert_eq!(eval_print(r#"(= #"a" (re-pattern #"a"))"#)?, "true");
+    assert_eq!(eval_print(r#"(= #"a" #"b")"#)?, "false");
+    // Patterns compiled again after the cache is cleared are still equal
+    assert_eq!(
+        eval_print(
+            r#"(let [a (re-pattern "a")] (dotimes [i 300] (re-pattern (str i))) (= a (re-pattern "a")))"#
+        )?,
+        "true"
+    );
+    assert_eq!(eval_print(r#"(get {#"a" 1} (re-pattern "a"))"#)?, "1");
+    assert_eq!(
+        eval_print(r#"(re-matcher #"a" "a")"#)?,
+        r##"#<matcher #"a">"##
+    );
+    let err = eval_print(r#"(re-pattern "(")"#).unwrap_err();
+    assert!(matches!(err.inner(), MalError::InvalidRegex(_)));
+    Ok(())
+}
+
+#[test]
+pub fn testing_regex_replace_and_split() -> Result<(), MalError> {
+    assert_eq!(
+        eval_print(r#"(str/replace "a1b22" #"\d+" "_")"#)?,
+        r#""a_b_""#
+    );
+    assert_eq!(
+        eval_print(r#"(str/replace "x=1 y=2" #"(\w)=(\d)" "$2=$1")"#)?,
+        r#""1=x 2=y""#
+    );
+    assert_eq!(
+        eval_print(r#"(str/replace "a1b2" #"\d" (fn* [d] (str "<" d ">")))"#)?,
+        r#""a<1>b<2>""#
+    );
+    assert_eq!(eval_print(r#"(str/replace "ab" #"(a)" first)"#)?, r#""ab""#);
+    assert_eq!(
+        eval_print(r#"(str/split "a1b22c" #"\d+")"#)?,
+        r#"["a" "b" "c"]"#
+    );
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+#[test]
+pub fn testing_seq_protocol() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(first "abc")"#)?, r"\a");
+    assert_eq!(eval_print(r#"(rest "abc")"#)?, r"(\b \c)");
+    assert_eq!(eval_print(r#"(count "abc")"#)?, "3");
+    assert_eq!(eval_print("(first {:a 1})")?, "[:a 1]");
+    assert_eq!(eval_print("(count {:a 1 :b 2})")?, "2");
+    assert_eq!(eval_print("(seq {:a 1})")?, "([:a 1])");
+    assert_eq!(eval_print("(first #{1})")?, "1");
+    assert_eq!(eval_print("(seq (lazy-seq nil))")?, "nil");
+    assert_eq!(eval_print("(nil? (seq (range 0)))")?, "true");
+    assert_eq!(eval_print("(take 2 (seq (range)))")?, "(0 1)");
+    for empty in ["()", "[]", "{}", "#{}", r#""""#, "nil"] {
+        assert_eq!(eval_print(&format!("(seq {})", empty))?, "nil");
+    }
+    assert_eq!(
+        eval_print("(if (seq (filter (fn* [x] (> x 5)) [1 3])) :some :none)")?,
+        ":none"
+    );
+    assert_eq!(eval_print("(first nil)")?, "nil");
+    assert_eq!(eval_print("(rest nil)")?, "()");
+    assert_eq!(eval_print("(count nil)")?, "0");
+    assert_eq!(eval_print(r#"(map (fn* [c] c) "ab")"#)?, r"(\a \b)");
+    assert_eq!(eval_print(r#"(vec "ab")"#)?, r"[\a \b]");
+    assert_eq!(eval_print(r#"(apply str (rest "abc"))"#)?, r#""bc""#);
+    let err = eval_print("(count 1)").unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`count`: argument 1 expected sequence, got 1"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_lazy_seq() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(def! calls (atom 0))", &env)?;
+    rep(
+        "(def! numbers (fn* [n] (lazy-seq (swap! calls (fn* [c] (+ c 1))) (cons n (numbers (+ n 1))))))",
+        &env,
+    )?;
+    // Printing the result of `def!` would realize the whole infinite sequence
+    rep("(do (def! s (numbers 0)) nil)", &env)?;
+    assert_eq!(rep("@calls", &env)?, "0");
+    assert_eq!(rep("(first (rest (rest s)))", &env)?, "2");
+    assert_eq!(rep("@calls", &env)?, "3");
+    assert_eq!(rep("(nth s 1)", &env)?, "1");
+    assert_eq!(rep("@calls", &env)?, "3");
+    assert_eq!(rep("(take 3 s)", &env)?, "(0 1 2)");
+    assert_eq!(rep("(lazy-seq nil)", &env)?, "()");
+    assert_eq!(rep("(sequential? s)", &env)?, "true");
+    assert_eq!(rep("(list? s)", &env)?, "false");
+    assert_eq!(rep("(empty? (lazy-seq []))", &env)?, "true");
+    Ok(())
+}
+
+#[test]
+pub fn testing_infinite_sequences() -> Result<(), MalError> {
+    assert_eq!(eval_print("(take 4 (range))")?, "(0 1 2 3)");
+    assert_eq!(eval_print("(range 3)")?, "(0 1 2)");
+    assert_eq!(eval_print("(range 2 5)")?, "(2 3 4)");
+    assert_eq!(eval_print("(range 10 0 -4)")?, "(10 6 2)");
+    assert_eq!(
+        eval_print("(take 4 (iterate (fn* [x] (* 2 x)) 1))")?,
+        "(1 2 4 8)"
+    );
+    assert_eq!(eval_print("(take 2 (repeat :a))")?, "(:a :a)");
+    assert_eq!(eval_print("(repeat 3 nil)")?, "(nil nil nil)");
+    assert_eq!(eval_print("(take 3 (drop 5 (range)))")?, "(5 6 7)");
+    assert_eq!(eval_print("(drop 2 [1 2 3])")?, "(3)");
+    assert_eq!(eval_print("(take 5 [1 2])")?, "(1 2)");
+    assert_eq!(
+        eval_print("(take-while (fn* [x] (< x 3)) (range))")?,
+        "(0 1 2)"
+    );
+    assert_eq!(
+        eval_print("(take 3 (map (fn* [x] (* x x)) (range)))")?,
+        "(0 1 4)"
+    );
+    assert_eq!(
+        eval_print("(nth (iterate (fn* [x] (+ x 1)) 0) 1000)")?,
+        "1000"
+    );
+    assert_eq!(
+        eval_print("(count (take 100000 (iterate (fn* [x] (+ x 1)) 0)))")?,
+        "100000"
+    );
+    assert_eq!(eval_print("(= (range 3) [0 1 2])")?, "true");
+    assert_eq!(eval_print("(= (range 3) (range 4))")?, "false");
+    Ok(())
+}
+
+#[test]
+pub fn testing_lazy_errors() -> Result<(), MalError> {
+    let env = Env::new();
+    rep("(do (def! s (map (fn* [x] (throw x)) (range))) nil)", &env)?;
+    let err = rep("s", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::Exception(_)));
+    let err = rep("(first s)", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::Exception(_)));
+    let err = rep("(take 2 (lazy-seq 1))", &env).unwrap_err();
+    assert_eq!(err.inner().to_string(), "Expected sequence");
+    let err = rep("(iterate 1 2)", &env).unwrap_err();
+    assert!(matches!(err.inner(), MalError::ArgType { .. }));
+
+    // Printing and comparing builtins return errors of the sequences they realize
+    rep(
+        "(def! inverses (fn* [] (map (fn* [x] (/ 1 x)) (range -1 2))))",
+        &env,
+    )?;
+    for input in [
+        "(prn (inverses))",
+        "(println (inverses))",
+        "(str (inverses))",
+        "(pr-str [1 (inverses)])",
+        "(= (inverses) (inverses))",
+        "[(inverses)]",
+        "{:a (inverses)}",
+    ] {
+        let err = rep(input, &env).unwrap_err();
+        assert!(matches!(err.inner(), MalError::DivisionByZero), "{}", input);
+    }
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use mal_core::{env::Env, rep, MalError};
+
+fn eval_print(input: &str) -> Result<String, MalError> {
+    let env = Env::new();
+    rep(input, &env)
+}
+
+#[test]
+pub fn testing_subs() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(str/subs "hello" 1)"#)?, r#""ello""#);
+    assert_eq!(eval_print(r#"(str/subs "hello" 1 3)"#)?, r#""el""#);
+    assert_eq!(eval_print(r#"(str/subs "hello" 5)"#)?, r#""""#);
+    assert_eq!(eval_print(r#"(str/subs "λx.x" 1 2)"#)?, r#""x""#);
+    assert!(eval_print(r#"(str/subs "hello" 6)"#).is_err());
+    assert!(eval_print(r#"(str/subs "hello" 3 2)"#).is_err());
+    let err = eval_print(r#"(str/subs "abc" -1)"#).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "-1 is out of bounds, index can't be negative"
+    );
+    let err = eval_print(r#"(str/subs "abc" 2 1)"#).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "Start index 2 is after end index 1"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_split_and_join() -> Result<(), MalError> {
+    assert_eq!(
+        eval_print(r#"(str/split "a,b,,c,," ",")"#)?,
+        r#"["a" "b" "" "c"]"#
+    );
+    assert_eq!(eval_print(r#"(str/split "a b" \space)"#)?, r#"["a" "b"]"#);
+    assert_eq!(eval_print(r#"(str/split "ab" "")"#)?, r#"["a" "b"]"#);
+    assert_eq!(eval_print(r#"(str/join [1 "a" :b])"#)?, r#""1a:b""#);
+    assert_eq!(
+        eval_print(r#"(str/join ", " (list 1 2 3))"#)?,
+        r#""1, 2, 3""#
+    );
+    assert_eq!(eval_print(r#"(str/join \- [])"#)?, r#""""#);
+    Ok(())
+}
+
+#[test]
+pub fn testing_case_and_trim() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(str/trim "  a b \n")"#)?, r#""a b""#);
+    assert_eq!(eval_print(r#"(str/upper-case "abc")"#)?, r#""ABC""#);
+    assert_eq!(eval_print(r#"(str/lower-case "ABC")"#)?, r#""abc""#);
+    Ok(())
+}
+
+#[test]
+pub fn testing_searching() -> Result<(), MalError> {
+    assert_eq!(eval_print(r#"(str/index-of "λabcabc" "bc")"#)?, "2");
+    assert_eq!(eval_print(r#"(str/index-of "abcabc" \b 2)"#)?, "4");
+    assert_eq!(eval_print(r#"(str/index-of "abc" "d")"#)?, "nil");
+    assert_eq!(eval_print(r#"(str/index-of "abc" "a" 10)"#)?, "nil");
+    assert_eq!(eval_print(r#"(str/starts-with? "abc" "ab")"#)?, "true");
+    assert_eq!(eval_print(r#"(str/ends-with? "abc" \c)"#)?, "true");
+    assert_eq!(eval_print(r#"(str/includes? "abc" "d")"#)?, "false");
+    assert_eq!(
+        eval_print(r#"(str/replace "a-b-c" "-" "+")"#)?,
+        r#""a+b+c""#
+    );
+    assert_eq!(eval_print(r#"(str/replace "a-b" \- \_)"#)?, r#""a_b""#);
+    let err = eval_print(r#"(str/includes? "abc" 1)"#).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`str/includes?`: argument 2 expected string or character, got 1"
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_format() -> Result<(), MalError> {
+    assert_eq!(
+        eval_print(r#"(str/format "%s is %d" "x" 10)"#)?,
+        r#""x is 10""#
+    );
+    assert_eq!(
+        eval_print(r#"(str/format "%.2f%%" 12.345)"#)?,
+        r#""12.35%""#
+    );
+    assert_eq!(eval_print(r#"(str/format "%f" 1)"#)?, r#""1.000000""#);
+    assert_eq!(eval_print(r#"(str/format "%s" [1 "a"])"#)?, r#""[1 a]""#);
+    let err = eval_print(r#"(str/format "%d" 1.5)"#).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "`str/format`: argument 2 expected integer, got 1.5"
+    );
+    let err = eval_print(r#"(str/format "%s %s" 1)"#).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "Invalid format string: missing argument for `%s`"
+    );
+    assert!(eval_print(r#"(str/format "%q" 1)"#).is_err());
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::{collections::BTreeMap, rc::Rc};
+
+use mal_core::{
+    env::Env,
+    eval_str, rep,
+    types::MalType,
+    value::{from_value, to_value},
+    MalError,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    depth: u32,
+    ratio: f64,
+    tags: Vec<String>,
+    parent: Option<Box<Config>>,
+    mode: Mode,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Fast,
+    Limit(i64),
+    Range(i64, i64),
+    Custom { level: u8 },
+}
+
+fn config() -> Config {
+    Config {
+        name: String::from("app"),
+        depth: 2,
+        ratio: 0.5,
+        tags: vec![String::from("a"), String::from("b")],
+        parent: None,
+        mode: Mode::Fast,
+    }
+}
+
+#[test]
+pub fn testing_to_value() -> Result<(), MalError> {
+    let env = Env::new();
+    env.set_global("config", to_value(&config())?);
+    assert_eq!(rep("(get config :name)", &env)?, r#""app""#);
+    assert_eq!(rep("(get config :depth)", &env)?, "2");
+    assert_eq!(rep("(get config :tags)", &env)?, r#"["a" "b"]"#);
+    assert_eq!(rep("(get config :parent)", &env)?, "nil");
+    assert_eq!(rep("(contains? config :parent)", &env)?, "true");
+    assert_eq!(rep("(get config :mode)", &env)?, ":Fast");
+    assert_eq!(rep("(count config)", &env)?, "6");
+
+    assert_eq!(to_value(&Mode::Limit(3))?.to_string(), "{:Limit 3}");
+    assert_eq!(to_value(&Mode::Range(1, 2))?.to_string(), "{:Range [1 2]}");
+    assert_eq!(
+        to_value(&Mode::Custom { level: 1 })?.to_string(),
+        "{:Custom {:level 1}}"
+    );
+    assert_eq!(format!("{:?}", to_value(&(1, 'x', ()))?), r"[1 \x nil]");
+    assert_eq!(to_value(&u64::MAX)?.to_string(), "18446744073709551615");
+    let map: BTreeMap<_, _> = vec![("k", 1)].into_iter().collect();
+    assert_eq!(format!("{:?}", to_value(&map)?), r#"{"k" 1}"#);
+    Ok(())
+}
+
+#[test]
+pub fn testing_from_value() -> Result<(), MalError> {
+    let env = Env::new();
+    let value = eval_str(
+        r#"{:name "app" :depth 2 :ratio 0.5 :tags '("a" b :c) :mode :Fast
+            :parent {:name "root" :depth 1 :ratio 1 :tags [] :parent nil :mode {:Limit 5}}}"#,
+        &env,
+    )?;
+    let config: Config = from_value(&value)?;
+    assert_eq!(config.name, "app");
+    assert_eq!(config.tags, vec!["a", "b", "c"]);
+    assert_eq!(config.mode, Mode::Fast);
+    let parent = config.parent.unwrap();
+    assert_eq!(parent.ratio, 1.0);
+    assert_eq!(parent.mode, Mode::Limit(5));
+    assert_eq!(parent.parent, None);
+
+    let value = eval_str("(take 3 (range))", &env)?;
+    assert_eq!(from_value::<Vec<i64>>(&value)?, vec![0, 1, 2]);
+    let value = eval_str("{:Custom {:level 3}}", &env)?;
+    assert_eq!(from_value::<Mode>(&value)?, Mode::Custom { level: 3 });
+    let value = eval_str(r#"{"a" 1 :b 2}"#, &env)?;
+    let map: BTreeMap<String, i64> = from_value(&value)?;
+    assert_eq!(
+        map.into_iter().collect::<Vec<_>>(),
+        vec![(String::from("a"), 1), (String::from("b"), 2)]
+    );
+    Ok(())
+}
+
+#[test]
+pub fn testing_round_trip() -> Result<(), MalError> {
+    let mut original = config();
+    original.mode = Mode::Range(-1, 1);
+    original.parent = Some(Box::new(config()));
+    let value = to_value(&original)?;
+    assert_eq!(from_value::<Config>(&value)?, original);
+
+    let env = Env::new();
+    env.set_global("config", value);
+    let updated = eval_str("(assoc-in config [:parent :depth] 10)", &env)?;
+    let updated: Config = from_value(&updated)?;
+    assert_eq!(updated.parent.unwrap().depth, 10);
+    Ok(())
+}
+
+#[test]
+pub fn testing_conversion_errors() -> Result<(), MalError> {
+    let env = Env::new();
+    let value = eval_str(r#"{:name "app"}"#, &env)?;
+    let err = from_value::<Config>(&value).unwrap_err();
+    assert_eq!(err.to_string(), "Conversion failed: missing field `depth`");
+
+    let value: Rc<dyn MalType> = eval_str("-1", &env)?;
+    assert!(matches!(
+        from_value::<u8>(&value).unwrap_err(),
+        MalError::Conversion(_)
+    ));
+    let value = eval_str("{:Fast 1 :Slow 2}", &env)?;
+    assert!(matches!(
+        from_value::<Mode>(&value).unwrap_err(),
+        MalError::Conversion(_)
+    ));
+    let value = eval_str("(atom 1)", &env)?;
+    assert!(from_value::<i64>(&value).is_err());
+    Ok(())
+}
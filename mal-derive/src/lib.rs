@@ -23,7 +23,7 @@ impl Parse for BuiltinArg {
         } else {
             return Err(lookahead.error());
         };
-        if input.is_empty() {
+        if input.is_empty() || input.peek(Token![,]) {
             Ok(Self::Flag(ident))
         } else {
             let lookahead = input.lookahead1();
@@ -43,21 +43,15 @@ impl Parse for BuiltinArg {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct BuiltinArgs {
     name: Option<Ident>,
     symbol: Option<syn::LitStr>,
     special: bool,
-}
-
-impl Default for BuiltinArgs {
-    fn default() -> Self {
-        Self {
-            name: Default::default(),
-            symbol: Default::default(),
-            special: false,
-        }
-    }
+    fold: bool,
+    chain: bool,
+    identity: Option<Lit>,
+    unary: Option<Lit>,
 }
 
 impl Parse for BuiltinArgs {
@@ -69,6 +63,10 @@ impl Parse for BuiltinArgs {
                 BuiltinArg::Flag(ident) => {
                     if ident == "special" {
                         args.special = true;
+                    } else if ident == "fold" {
+                        args.fold = true;
+                    } else if ident == "chain" {
+                        args.chain = true;
                     } else {
                         return Err(syn::Error::new(
                             ident.span(),
@@ -99,6 +97,21 @@ impl Parse for BuiltinArgs {
                             }
                         };
                         args.symbol = Some(symbol.clone());
+                    } else if ident == "identity" || ident == "unary" {
+                        match lit {
+                            Lit::Int(_) | Lit::Float(_) => {}
+                            _ => {
+                                return Err(syn::Error::new(
+                                    lit.span(),
+                                    format!("`{}` attribute expects numeric value.", ident),
+                                ))
+                            }
+                        };
+                        if ident == "identity" {
+                            args.identity = Some(lit.clone());
+                        } else {
+                            args.unary = Some(lit.clone());
+                        }
                     } else {
                         return Err(syn::Error::new(
                             ident.span(),
@@ -109,10 +122,42 @@ impl Parse for BuiltinArgs {
             }
         }
 
+        if args.fold && args.chain {
+            return Err(syn::Error::new(
+                input.span(),
+                "Builtins can't be both `fold` and `chain`.",
+            ));
+        }
+        if let (Some(_), Some(unary)) = (&args.identity, &args.unary) {
+            return Err(syn::Error::new(
+                unary.span(),
+                "`identity` is already used for unary calls.",
+            ));
+        }
+        if let Some(lit) = args.identity.as_ref().or(args.unary.as_ref()) {
+            if !args.fold {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "`identity` and `unary` attributes require `fold`.",
+                ));
+            }
+        }
+
         Ok(args)
     }
 }
 
+fn lit_to_mal_type(lit: &Lit) -> proc_macro2::TokenStream {
+    match lit {
+        Lit::Float(_) => quote! {
+            std::rc::Rc::from(MalFloat::from(#lit)) as std::rc::Rc<dyn MalType>
+        },
+        _ => quote! {
+            std::rc::Rc::from(MalInt::from(#lit)) as std::rc::Rc<dyn MalType>
+        },
+    }
+}
+
 fn is_option(type_path: &syn::TypePath) -> bool {
     let option = vec![
         String::from("std"),
@@ -142,7 +187,7 @@ fn is_rc_mal_type(type_path: &syn::TypePath) -> bool {
     if !is_rc(type_path) {
         return false;
     }
-    let mal_type_path = vec![
+    let mal_type_path = [
         String::from("mal"),
         String::from("types"),
         String::from("MalType"),
@@ -226,7 +271,7 @@ fn compare_segments(type_path: &syn::TypePath, segments: Vec<String>) -> bool {
     true
 }
 
-const TYPE_ERROR_MSG : &str = "Builtin function args should be references to types that implement `MalType`, `&Rc<dyn MalType>`, `Option<&Rc<dyn MalType>>`, or `&Rc<Env>`.";
+const TYPE_ERROR_MSG : &str = "Builtin function args should be references to types that implement `MalType`, `&Rc<dyn MalType>`, `Option<&Rc<dyn MalType>>`, `&Rc<Env>`, or types that implement `TryFrom<&dyn MalType>`.";
 
 #[proc_macro_attribute]
 pub fn builtin_func(attr: TokenStream, input: TokenStream) -> TokenStream {
@@ -246,12 +291,12 @@ pub fn builtin_func(attr: TokenStream, input: TokenStream) -> TokenStream {
     let pair_name = Ident::new(&builtin.to_uppercase(), name.span());
 
     // Symbol for env
+    let symbol = if let Some(symbol) = &args.symbol {
+        symbol.clone()
+    } else {
+        syn::LitStr::new(&name.to_string(), name.span())
+    };
     let pair = if !args.special {
-        let symbol = if let Some(symbol) = args.symbol {
-            symbol
-        } else {
-            syn::LitStr::new(&name.to_string(), name.span())
-        };
         quote! {
             pub const #pair_name: (&'static str, &'static MalFuncPtr) = (#symbol, &#builtin_name);
         }
@@ -262,6 +307,18 @@ pub fn builtin_func(attr: TokenStream, input: TokenStream) -> TokenStream {
     // Return type of the builtin function
     let return_type = &func.sig.output;
 
+    // Position of the argument reported in type errors, `fold` and `chain` builtins call the
+    // original function with pairs of arguments so position is offset by pair index
+    let binary = args.fold || args.chain;
+    let arg_position = |index: usize| {
+        if binary {
+            quote! { #index + offset }
+        } else {
+            let position = index + 1;
+            quote! { #position }
+        }
+    };
+
     // Gather arg names, and types of the reference from signature
     let mut arg_count = func.sig.inputs.len();
     let mut optional_count = 0;
@@ -298,6 +355,15 @@ pub fn builtin_func(attr: TokenStream, input: TokenStream) -> TokenStream {
                 });
                 continue;
             }
+            // Owned arguments are converted from `&dyn MalType` using `TryFrom`
+            syn::Type::Path(ty) => {
+                let position = arg_position(index);
+                arg_statements.push(quote! {
+                    let #arg_ident: #ty = std::convert::TryFrom::try_from(args[#index].as_ref())
+                        .map_err(|err| MalError::from(err).for_arg(#symbol, #position, &args[#index]))?;
+                });
+                continue;
+            }
             _ => {
                 return syn::Error::new(pat_type.ty.span(), TYPE_ERROR_MSG)
                     .to_compile_error()
@@ -314,9 +380,14 @@ pub fn builtin_func(attr: TokenStream, input: TokenStream) -> TokenStream {
             syn::Type::Path(ty) if is_rc(ty) => quote! {
                 let #arg_ident = &args[#index];
             },
-            syn::Type::Path(ty) => quote! {
-                let #arg_ident = &args[#index].as_type::<#ty>()?;
-            },
+            syn::Type::Path(ty) => {
+                let position = arg_position(index);
+                quote! {
+                    let #arg_ident = &args[#index]
+                        .as_type::<#ty>()
+                        .map_err(|err| err.for_arg(#symbol, #position, &args[#index]))?;
+                }
+            }
             syn::Type::TraitObject(_) => quote! {
                 let #arg_ident = args[#index].as_ref();
             },
@@ -349,40 +420,141 @@ pub fn builtin_func(attr: TokenStream, input: TokenStream) -> TokenStream {
         #original_name(#(#arg_names),*)
     };
 
+    // Error for calls with wrong number of arguments, `expected` describes the accepted counts
+    let wrong_arity = |expected: String| {
+        quote! {
+            std::result::Result::Err(MalError::WrongArity {
+                name: std::string::String::from(#symbol),
+                expected: std::string::String::from(#expected),
+                actual: args.len(),
+            })
+        }
+    };
+
     // Generate code to check for function argument count
     let arg_count_check = if !variadic && optional_count == 0 {
+        let err = wrong_arity(arg_count.to_string());
         quote! {
             if args.len() != #arg_count {
-                return std::result::Result::Err(MalError::TypeError);
+                return #err;
             }
         }
     } else if variadic && arg_count > 1 {
         // Variadic functions that don't take positional arguments should not generate check
         // since every arg is passed to the function
         let actual_arg_count = arg_count - 1;
+        let err = wrong_arity(format!("at least {}", actual_arg_count));
         quote! {
             if args.len() < #actual_arg_count {
-                return std::result::Result::Err(MalError::TypeError);
+                return #err;
             }
         }
     } else if optional_count != 0 {
         let max_count = arg_count + optional_count + 1;
+        let expected = if optional_count == 1 {
+            format!("{} or {}", arg_count, arg_count + 1)
+        } else {
+            format!("{} to {}", arg_count, arg_count + optional_count)
+        };
+        let err = wrong_arity(expected);
         quote! {
             if !(#arg_count..#max_count).contains(&args.len()) {
-                return std::result::Result::Err(MalError::TypeError);
+                return #err;
             }
         }
     } else {
         quote! {}
     };
 
-    let generated = quote! {
-        #func
-        pub fn #builtin_name(args: &[std::rc::Rc<dyn MalType>], env: &std::rc::Rc<Env>) #return_type {
+    if binary && (arg_count != 2 || variadic || optional_count != 0) {
+        return syn::Error::new(
+            func.sig.inputs.span(),
+            "`fold` and `chain` builtins should take exactly two positional arguments.",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // `fold` and `chain` builtins are variadic, original function is called for each pair of
+    // arguments and arity checks are done for the whole argument list
+    let body = if args.fold {
+        let nullary = match &args.identity {
+            Some(identity) => {
+                let identity = lit_to_mal_type(identity);
+                quote! { std::result::Result::Ok(#identity) }
+            }
+            None => wrong_arity(String::from("at least 1")),
+        };
+        let unary = match args.identity.as_ref().or(args.unary.as_ref()) {
+            Some(lhs) => {
+                let lhs = lit_to_mal_type(lhs);
+                quote! { binary(&[#lhs, args[0].clone()], env, 0) }
+            }
+            None => quote! { std::result::Result::Ok(args[0].clone()) },
+        };
+        quote! {
+            #[allow(unused_variables)]
+            fn binary(
+                args: &[std::rc::Rc<dyn MalType>],
+                env: &std::rc::Rc<Env>,
+                offset: usize,
+            ) #return_type {
+                #(#arg_statements)*
+                #actual_call
+            }
+            match args.len() {
+                0 => #nullary,
+                1 => #unary,
+                _ => {
+                    let mut acc = args[0].clone();
+                    for (offset, arg) in args.iter().enumerate().skip(1) {
+                        acc = binary(&[acc, arg.clone()], env, offset)?;
+                    }
+                    std::result::Result::Ok(acc)
+                }
+            }
+        }
+    } else if args.chain {
+        let no_args = wrong_arity(String::from("at least 1"));
+        quote! {
+            #[allow(unused_variables)]
+            fn binary(
+                args: &[std::rc::Rc<dyn MalType>],
+                env: &std::rc::Rc<Env>,
+                offset: usize,
+            ) #return_type {
+                #(#arg_statements)*
+                #actual_call
+            }
+            match args.len() {
+                0 => #no_args,
+                // Single argument is still type checked by comparing it with itself
+                1 => binary(&[args[0].clone(), args[0].clone()], env, 1)
+                    .map(|_| std::rc::Rc::from(MalBool::from(true)) as std::rc::Rc<dyn MalType>),
+                _ => {
+                    for (index, pair) in args.windows(2).enumerate() {
+                        let result = binary(pair, env, index + 1)?;
+                        if !result.truthy() {
+                            return std::result::Result::Ok(result);
+                        }
+                    }
+                    std::result::Result::Ok(std::rc::Rc::from(MalBool::from(true)))
+                }
+            }
+        }
+    } else {
+        quote! {
             #arg_count_check
             #(#arg_statements)*
             #actual_call
         }
+    };
+
+    let generated = quote! {
+        #func
+        pub fn #builtin_name(args: &[std::rc::Rc<dyn MalType>], env: &std::rc::Rc<Env>) #return_type {
+            #body
+        }
         #pair
     };
     generated.into()
@@ -10,7 +10,7 @@ use mal_derive::builtin_func;
 
 #[builtin_func]
 pub fn add(lhs: &MalInt, rhs: &MalInt) -> MalResult {
-    Ok(Rc::from(lhs + rhs))
+    Ok(Rc::from(MalInt::from(lhs.value() + rhs.value())))
 }
 
 fn main() {
@@ -14,9 +14,9 @@ pub fn swap(atom: &MalAtom, callable: &Rc<dyn MalType>, args: &[Rc<dyn MalType>]
     if let Ok(func) = callable.as_type() {
         atom.update_with_fn(func, args, &env)
     } else if let Ok(clojure) = callable.as_type() {
-        atom.update_with_clojure(clojure, args, &env)
+        atom.update_with_clojure(clojure, args)
     } else {
-        Err(MalError::TypeError)
+        Err(MalError::NotCallable(callable.clone()))
     }
 }
 
@@ -18,9 +18,9 @@ pub fn swap(
     if let Ok(func) = callable.as_type() {
         atom.update_with_fn(func, args, env)
     } else if let Ok(clojure) = callable.as_type() {
-        atom.update_with_clojure(clojure, args, env)
+        atom.update_with_clojure(clojure, args)
     } else {
-        Err(MalError::TypeError)
+        Err(MalError::NotCallable(callable.clone()))
     }
 }
 
@@ -12,7 +12,7 @@ pub fn let_fn(
 ) -> Result<(Rc<dyn MalType>, Rc<Env>), MalError> {
     let env_list = bindings.as_array()?;
     if env_list.len() % 2 != 0 {
-        return Err(MalError::TypeError);
+        return Err(MalError::WrongType("even number of binding forms"));
     }
 
     let new_env = Env::with_outer(env.clone());
@@ -11,7 +11,7 @@ use mal_derive::builtin_func;
 
 #[builtin_func(symbol = "/")]
 pub fn divide(lhs: &MalInt, rhs: &MalInt) -> MalResult {
-    Ok(Rc::from(lhs / rhs))
+    Ok(Rc::from(MalInt::from(lhs.value() / rhs.value())))
 }
 
 #[builtin_func]
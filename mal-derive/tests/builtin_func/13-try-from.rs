@@ -0,0 +1,23 @@
+use std::rc::Rc;
+
+use mal_core::{
+    env::Env,
+    types::{func::MalFuncPtr, MalBool, MalFloat, MalInt, MalType, Number},
+    MalError, MalResult,
+};
+
+use mal_derive::builtin_func;
+
+#[builtin_func(symbol = "<")]
+pub fn lt(lhs: Number, rhs: Number) -> MalResult {
+    Ok(Rc::from(MalBool::from(lhs < rhs)))
+}
+
+fn main() {
+    let lhs: Rc<dyn MalType> = Rc::from(MalInt::from(1));
+    let rhs: Rc<dyn MalType> = Rc::from(MalFloat::from(1.5));
+    let args = vec![lhs, rhs];
+    let env = Env::new();
+
+    assert!(mal_lt(args.as_slice(), &env).unwrap().truthy());
+}
@@ -0,0 +1,36 @@
+use std::rc::Rc;
+
+use mal_core::{
+    env::Env,
+    types::{func::MalFuncPtr, MalFloat, MalInt, MalType, Number},
+    MalError, MalResult,
+};
+
+use mal_derive::builtin_func;
+
+#[builtin_func(symbol = "+", fold, identity = 0)]
+pub fn add(lhs: Number, rhs: Number) -> MalResult {
+    Ok((lhs + rhs).into())
+}
+
+#[builtin_func(symbol = "-", fold, unary = 0.0)]
+pub fn subtract(lhs: Number, rhs: Number) -> MalResult {
+    Ok((lhs - rhs).into())
+}
+
+fn main() {
+    let one: Rc<dyn MalType> = Rc::from(MalInt::from(1));
+    let two: Rc<dyn MalType> = Rc::from(MalInt::from(2));
+    let env = Env::new();
+
+    let sum = mal_add(&[], &env).unwrap();
+    assert!(sum.equal(&MalInt::from(0)));
+    let sum = mal_add(&[one.clone(), two.clone(), two.clone()], &env).unwrap();
+    assert!(sum.equal(&MalInt::from(5)));
+
+    assert!(mal_subtract(&[], &env).is_err());
+    let negated = mal_subtract(&[one.clone()], &env).unwrap();
+    assert!(negated.equal(&MalFloat::from(-1.0)));
+    let difference = mal_subtract(&[two, one.clone(), one], &env).unwrap();
+    assert!(difference.equal(&MalInt::from(0)));
+}
@@ -0,0 +1,27 @@
+use std::rc::Rc;
+
+use mal_core::{
+    env::Env,
+    types::{func::MalFuncPtr, MalBool, MalInt, MalString, MalType, Number},
+    MalError, MalResult,
+};
+
+use mal_derive::builtin_func;
+
+#[builtin_func(symbol = "<", chain)]
+pub fn lt(lhs: Number, rhs: Number) -> MalResult {
+    Ok(Rc::from(MalBool::from(lhs < rhs)))
+}
+
+fn main() {
+    let one: Rc<dyn MalType> = Rc::from(MalInt::from(1));
+    let two: Rc<dyn MalType> = Rc::from(MalInt::from(2));
+    let string: Rc<dyn MalType> = Rc::from(MalString::from("string"));
+    let env = Env::new();
+
+    assert!(mal_lt(&[], &env).is_err());
+    assert!(mal_lt(&[one.clone()], &env).unwrap().truthy());
+    assert!(mal_lt(&[string], &env).is_err());
+    assert!(mal_lt(&[one.clone(), two.clone()], &env).unwrap().truthy());
+    assert!(!mal_lt(&[one.clone(), two, one], &env).unwrap().truthy());
+}
@@ -0,0 +1,31 @@
+use std::rc::Rc;
+
+use mal_core::{
+    env::Env,
+    types::{func::MalFuncPtr, MalAtom, MalInt, MalType},
+    MalError, MalResult,
+};
+
+use mal_derive::builtin_func;
+
+#[builtin_func]
+pub fn deref(atom: &MalAtom) -> MalResult {
+    Ok(atom.value())
+}
+
+fn main() {
+    let arg: Rc<dyn MalType> = Rc::from(MalInt::from(5));
+    let args = vec![arg.clone()];
+    let env = Env::new();
+
+    let err = mal_deref(args.as_slice(), &env).unwrap_err();
+    assert_eq!(
+        err,
+        MalError::ArgType {
+            builtin: "deref",
+            position: 1,
+            expected: "atom",
+            actual: arg,
+        }
+    );
+}
@@ -0,0 +1,55 @@
+use std::rc::Rc;
+
+use mal_core::{
+    env::Env,
+    types::{func::MalFuncPtr, MalInt, MalType},
+    MalError, MalResult,
+};
+
+use mal_derive::builtin_func;
+
+#[builtin_func]
+pub fn pair(first: &Rc<dyn MalType>, _second: &Rc<dyn MalType>) -> MalResult {
+    Ok(first.clone())
+}
+
+#[builtin_func(symbol = "at-least-one")]
+pub fn at_least_one(first: &Rc<dyn MalType>, _rest: &[Rc<dyn MalType>]) -> MalResult {
+    Ok(first.clone())
+}
+
+#[builtin_func]
+pub fn optional(
+    first: &Rc<dyn MalType>,
+    _second: Option<&Rc<dyn MalType>>,
+    _third: Option<&Rc<dyn MalType>>,
+) -> MalResult {
+    Ok(first.clone())
+}
+
+fn message(result: MalResult) -> String {
+    result.unwrap_err().to_string()
+}
+
+fn main() {
+    let one: Rc<dyn MalType> = Rc::from(MalInt::from(1));
+    let env = Env::new();
+
+    assert_eq!(
+        message(mal_pair(&[one.clone()], &env)),
+        "`pair` expected 2 arguments, got 1"
+    );
+    assert_eq!(
+        message(mal_at_least_one(&[], &env)),
+        "`at-least-one` expected at least 1 argument, got 0"
+    );
+    assert_eq!(
+        message(mal_optional(&[], &env)),
+        "`optional` expected 1 to 3 arguments, got 0"
+    );
+    assert!(mal_optional(&[one.clone(), one.clone(), one], &env).is_ok());
+    assert!(matches!(
+        mal_pair(&[], &env),
+        Err(MalError::WrongArity { actual: 0, .. })
+    ));
+}
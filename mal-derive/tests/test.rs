@@ -13,4 +13,9 @@ fn builtin_func_test() {
     t.pass("tests/builtin_func/10-option.rs");
     t.pass("tests/builtin_func/11-return-type.rs");
     t.pass("tests/builtin_func/12-pair.rs");
+    t.pass("tests/builtin_func/13-try-from.rs");
+    t.pass("tests/builtin_func/14-fold.rs");
+    t.pass("tests/builtin_func/15-chain.rs");
+    t.pass("tests/builtin_func/16-arg-type.rs");
+    t.pass("tests/builtin_func/17-arity.rs");
 }
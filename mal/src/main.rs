@@ -1,8 +1,18 @@
-use std::{borrow::Cow, fmt::Write, rc::Rc};
+use std::{
+    borrow::Cow,
+    fmt::Write,
+    fs,
+    io::{self, IsTerminal, Read},
+    iter,
+    path::PathBuf,
+    process,
+    rc::Rc,
+};
 
 use mal_core::{
-    env::Env,
-    reader::{AtomKind, ParseError, Reader, Token, Tokenizer},
+    env::{self, Env, USER_NAMESPACE},
+    reader::{AtomKind, ParseError, Reader, Source, Span, Token, Tokenizer},
+    MalError,
 };
 use rustyline::{
     completion::Completer,
@@ -13,6 +23,13 @@ use rustyline::{
     Config, Editor, Helper,
 };
 
+fn slice(line: &str, span: Span) -> String {
+    line.chars()
+        .skip(span.start)
+        .take(span.stop - span.start)
+        .collect()
+}
+
 pub struct MalHelper {
     env: Rc<Env>,
 }
@@ -38,7 +55,9 @@ impl Completer for MalHelper {
             match maybe_token {
                 Ok(token) if token.stop >= pos => {
                     if let Token::Atom(atom) = token.as_token() {
-                        return Ok((token.start, self.env.starts_with(atom)));
+                        let env =
+                            env::current_namespace(&self.env).unwrap_or_else(|| self.env.clone());
+                        return Ok((token.start, env.starts_with(atom)));
                     }
                 }
                 Ok(_) | Err(_) => {}
@@ -63,17 +82,19 @@ impl Highlighter for MalHelper {
                 Ok(full_token) => full_token,
                 Err(e) => {
                     match e {
-                        ParseError::UnbalancedEmptyString => {
-                            owned.write_str("\x1b[1;31m\"\x1b[0m").unwrap()
-                        }
-                        ParseError::UnbalancedString(string) => owned
-                            .write_fmt(format_args!("\x1b[1;31m\"{}\"\x1b[0m", string))
+                        ParseError::UnbalancedEmptyString(span)
+                        | ParseError::UnbalancedString(_, span)
+                        | ParseError::InvalidChar(_, span) => owned
+                            .write_fmt(format_args!("\x1b[1;31m{}\x1b[0m", slice(line, span)))
                             .unwrap(),
-                        ParseError::EOF
-                        | ParseError::UnbalancedList
-                        | ParseError::UnbalancedVec
-                        | ParseError::UnbalancedMap
-                        | ParseError::UnexpectedToken(_) => (),
+                        ParseError::EOF(_)
+                        | ParseError::UnbalancedList(_)
+                        | ParseError::UnbalancedVec(_)
+                        | ParseError::UnbalancedMap(_)
+                        | ParseError::OddMapLiteral(_)
+                        | ParseError::InvalidRegex(..)
+                        | ParseError::NestedFnLiteral(_)
+                        | ParseError::UnexpectedToken(..) => (),
                     }
                     continue;
                 }
@@ -85,6 +106,9 @@ impl Highlighter for MalHelper {
                 | Token::RightSquare
                 | Token::LeftCurly
                 | Token::RightCurly
+                | Token::HashCurly
+                | Token::HashParen
+                | Token::HashUnderscore
                 | Token::LeftParen
                 | Token::RightParen
                 | Token::Apostrophe
@@ -97,9 +121,12 @@ impl Highlighter for MalHelper {
                 | Token::Tab
                 | Token::Comma
                 | Token::At => owned.write_fmt(format_args!("{}", token)).unwrap(),
-                Token::String(string) => {
+                Token::String(_) | Token::Regex(_) | Token::Char(_) => {
                     owned
-                        .write_fmt(format_args!("\x1b[1;31m\"{}\"\x1b[0m", string))
+                        .write_fmt(format_args!(
+                            "\x1b[1;31m{}\x1b[0m",
+                            slice(line, full_token.span())
+                        ))
                         .unwrap();
                 }
                 Token::Comment(comment) => {
@@ -118,7 +145,7 @@ impl Highlighter for MalHelper {
                     AtomKind::Nil => owned
                         .write_fmt(format_args!("\x1b[1;34m{}\x1b[0m", atom))
                         .unwrap(),
-                    AtomKind::Int => owned.write_str(atom).unwrap(),
+                    AtomKind::Int | AtomKind::Float => owned.write_str(atom).unwrap(),
                     AtomKind::Builtin => owned
                         .write_fmt(format_args!("\x1b[1;35m{}\x1b[0m", atom))
                         .unwrap(),
@@ -142,18 +169,21 @@ impl Validator for MalHelper {
         let mut square = 0;
         let mut paren = 0;
         for maybe_token in reader {
-            let token = match maybe_token {
-                Ok(token) => token,
+            let full_token = match maybe_token {
+                Ok(full_token) => full_token,
+                // Invalid character literals can't be completed, so input is passed on to report the error
+                Err(ParseError::InvalidChar(..)) => return Ok(ValidationResult::Valid(None)),
                 Err(_) => return Ok(ValidationResult::Incomplete),
             };
-            match token {
+            match full_token.as_token() {
                 Token::LeftSquare => square += 1,
                 Token::RightSquare => square -= 1,
-                Token::LeftCurly => curly += 1,
+                Token::LeftCurly | Token::HashCurly => curly += 1,
                 Token::RightCurly => curly -= 1,
-                Token::LeftParen => paren += 1,
+                Token::LeftParen | Token::HashParen => paren += 1,
                 Token::RightParen => paren -= 1,
                 Token::TildeAt
+                | Token::HashUnderscore
                 | Token::Apostrophe
                 | Token::BackTick
                 | Token::Tilde
@@ -165,6 +195,8 @@ impl Validator for MalHelper {
                 | Token::Tab
                 | Token::Comma
                 | Token::String(_)
+                | Token::Regex(_)
+                | Token::Char(_)
                 | Token::Comment(_)
                 | Token::Atom(_) => {}
             }
@@ -183,23 +215,96 @@ impl Validator for MalHelper {
 
 impl Helper for MalHelper {}
 
-fn main() {
+const USAGE: &str = "Usage: mal [<script> | -e <expr> | -] [args...]";
+
+fn report(err: &MalError) {
+    eprintln!("{}", err);
+    if let Some(location) = err.location() {
+        eprintln!("{}", location.snippet());
+    }
+    if let Some(stack_trace) = err.stack_trace() {
+        eprintln!("{}", stack_trace);
+    }
+}
+
+fn repl(mut env: Rc<Env>) {
     let config = Config::builder().auto_add_history(true).build();
     let mut editor = Editor::<MalHelper>::with_config(config);
-    let env = Env::new();
 
     editor.set_helper(Some(MalHelper::from(env.clone())));
 
     loop {
-        let readline = editor.readline("user> ");
+        let prompt = format!("{}> ", env.namespace_name().unwrap_or(USER_NAMESPACE));
+        let readline = editor.readline(&prompt);
         match readline {
-            Ok(line) => match mal_core::rep(line.as_str(), &env) {
-                Ok(result) => println!("{}", result),
-                Err(err) => eprintln!("{}", err),
-            },
+            Ok(line) => {
+                match mal_core::rep(line.as_str(), &env) {
+                    Ok(result) => println!("{}", result),
+                    Err(err) => report(&err),
+                }
+                // `ns` switches the namespace following lines are evaluated in
+                if let Some(namespace) = env::current_namespace(&env) {
+                    env = namespace;
+                }
+            }
             Err(ReadlineError::Eof) => break,
             Err(ReadlineError::Interrupted) => break,
             Err(err) => eprintln!("Unexpected error encountered {}.", err),
         }
     }
 }
+
+fn main() {
+    let env = Env::new();
+    // Namespaces are looked up in the working directory, then in the directories of `MAL_PATH`
+    if let Some(paths) = std::env::var_os("MAL_PATH") {
+        env.set_load_path(iter::once(PathBuf::from(".")).chain(std::env::split_paths(&paths)));
+    }
+    let mut args = std::env::args().skip(1);
+
+    // Program is read from the script path, `-e` argument or stdin, remaining arguments are
+    // passed to the program as `*ARGV*`
+    let arg = args.next();
+    let is_expr = arg.as_deref() == Some("-e");
+    let source = match arg.as_deref() {
+        None if io::stdin().is_terminal() => return repl(env),
+        None | Some("-") => {
+            let mut text = String::new();
+            if let Err(err) = io::stdin().read_to_string(&mut text) {
+                eprintln!("Couldn't read program from stdin: {}", err);
+                process::exit(1);
+            }
+            Source::new("<stdin>", text)
+        }
+        Some("-e") => match args.next() {
+            Some(expr) => Source::new("<expr>", expr),
+            None => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        },
+        Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            return;
+        }
+        Some(path) => match fs::read_to_string(path) {
+            Ok(text) => Source::new(path, text),
+            Err(err) => {
+                eprintln!("Couldn't read `{}`: {}", path, err);
+                process::exit(1);
+            }
+        },
+    };
+    env.init_argv(args);
+
+    let result = mal_core::eval_source(&Rc::from(source), &env)
+        .and_then(|result| mal_core::seq::realize(&result).map(|_| result));
+    match result {
+        Ok(result) if is_expr => println!("{}", mal_core::print(result)),
+        Ok(_) => {}
+        Err(err) => {
+            report(&err);
+            process::exit(1);
+        }
+    }
+}